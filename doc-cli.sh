@@ -1,7 +1,7 @@
 #!/usr/bin/env bash
 
 # doc-cli.sh - Wrapper for documentation CLI tools
-# This script builds all Rust modules and provides a unified interface to the doc-cli tools
+# This script keeps the Rust binaries up to date and provides a unified interface to the doc-cli tools
 
 set -e  # Exit immediately if a command exits with a non-zero status
 
@@ -35,52 +35,42 @@ show_spinner() {
   printf "    \b\b\b\b"
 }
 
-# Function to compile a Rust file
-compile_rust_file() {
-  local source_file="$1"
-  local binary_name="$(basename "$source_file" .rs)"
-  local output_path="${RELEASE_DIR}/${binary_name}"
-  
-  echo -e "${BLUE}Compiling ${binary_name}...${NC}"
+# Build all binaries through cargo (they share the doc_tools library)
+compile_all_rust_files() {
+  echo -e "\n${YELLOW}Building documentation CLI tools...${NC}\n"
   
-  # Run the compilation in the background and show a spinner
-  rustc -o "${output_path}" "${source_file}" &
-  show_spinner $!
+  # Run the build in the background and show a spinner
+  cargo build --release --quiet --manifest-path "${SRC_DIR}/Cargo.toml" &
+  local build_pid=$!
+  show_spinner $build_pid
   
-  if [ -f "${output_path}" ]; then
-    echo -e "${GREEN}✓ ${binary_name} compiled successfully${NC}"
-    # Make the binary executable
-    chmod +x "${output_path}"
-  else 
-    echo -e "${RED}✗ Failed to compile ${binary_name}${NC}"
+  if wait $build_pid; then
+    echo -e "\n${GREEN}All tools built successfully!${NC}\n"
+  else
+    echo -e "${RED}✗ Failed to build documentation CLI tools${NC}"
     exit 1
   fi
 }
 
-# Compile all Rust files
-compile_all_rust_files() {
-  echo -e "\n${YELLOW}Building documentation CLI tools...${NC}\n"
-  
-  # Find all Rust files in the scripts directory and compile them
-  for rust_file in "${SRC_DIR}"/*.rs; do
-    if [ -f "$rust_file" ]; then
-      compile_rust_file "$rust_file"
-    fi
-  done
-  
-  echo -e "\n${GREEN}All tools built successfully!${NC}\n"
+# Build the tools on first use, afterwards only when their sources changed
+ensure_fresh_build() {
+  local doc_cli_path="${RELEASE_DIR}/doc-cli"
+
+  if [ ! -x "$doc_cli_path" ]; then
+    compile_all_rust_files
+  fi
+
+  # Compares a hash of the sources with the one recorded by the last build
+  if ! "${doc_cli_path}" --quiet self rebuild --if-stale; then
+    echo -e "${RED}✗ Failed to rebuild documentation CLI tools${NC}"
+    exit 1
+  fi
 }
 
 # Function to run the doc-cli tool
 run_doc_cli() {
   local doc_cli_path="${RELEASE_DIR}/doc-cli"
-  
-  if [ ! -f "$doc_cli_path" ]; then
-    echo -e "${RED}Error: doc-cli not found at ${doc_cli_path}${NC}"
-    echo -e "Attempting to build it now..."
-    compile_rust_file "${SRC_DIR}/doc-cli.rs"
-  fi
-  
+
   # Pass any arguments to the doc-cli tool
   "${doc_cli_path}" "$@"
 }
@@ -91,8 +81,7 @@ echo -e "${BLUE}=====================================${NC}"
 echo -e "${BLUE}       Documentation CLI Tools       ${NC}"
 echo -e "${BLUE}=====================================${NC}\n"
 
-# Build all tools first
-compile_all_rust_files
+ensure_fresh_build
 
 # Run the doc-cli tool with any arguments
-run_doc_cli "$@"
\ No newline at end of file
+run_doc_cli "$@"
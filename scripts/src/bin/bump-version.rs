@@ -0,0 +1,20 @@
+use std::process;
+
+use clap::Parser;
+use doc_tools::cli::BumpVersionCli;
+use doc_tools::{commands, output, Context};
+
+fn main() {
+    let cli = BumpVersionCli::parse();
+
+    let result = Context::load(&cli.global).and_then(|ctx| commands::bump_version(&ctx, &cli.args));
+
+    if let Err(e) = result {
+        if cli.global.output.is_json() {
+            output::emit_error("bump-version", &e);
+        } else {
+            eprintln!("Error: {}", e);
+        }
+        process::exit(e.exit_code());
+    }
+}
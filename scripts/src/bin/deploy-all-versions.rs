@@ -0,0 +1,21 @@
+use std::process;
+
+use clap::Parser;
+use doc_tools::cli::DeployCli;
+use doc_tools::style::{NC, RED};
+use doc_tools::{commands, output, Context};
+
+fn main() {
+    let cli = DeployCli::parse();
+
+    let result = Context::load(&cli.global).and_then(|ctx| commands::deploy(&ctx, &cli.args));
+
+    if let Err(e) = result {
+        if cli.global.output.is_json() {
+            output::emit_error("deploy", &e);
+        } else {
+            doc_tools::error!("{}Error: {}{}", RED, e, NC);
+        }
+        process::exit(e.exit_code());
+    }
+}
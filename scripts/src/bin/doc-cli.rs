@@ -0,0 +1,273 @@
+use std::cell::OnceCell;
+use std::io::{self, IsTerminal};
+
+use clap::error::ErrorKind;
+use clap::Parser;
+use doc_tools::cli::{self, BumpVersionArgs, Cli, Command, DeployArgs, GlobalArgs, StartupArgs};
+use doc_tools::plugins::{self, Plugin};
+use doc_tools::tui::{self, MenuOutcome};
+use doc_tools::{commands, info, output, prompt, rebuild, say, style, Context, DocToolsError, Project};
+
+fn main() {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        // Help lists the plugins, so only now look for them
+        Err(e) if e.kind() == ErrorKind::DisplayHelp => {
+            let project = Project::resolve(None).ok();
+            let plugins = plugins::discover(project.as_ref().map(Project::root));
+            cli::command_with_plugins(&plugins).get_matches();
+            e.exit()
+        }
+        Err(e) => e.exit(),
+    };
+
+    let mut app = DocCli::new(cli);
+    app.run();
+}
+
+// DocCli struct to handle all documentation utilities
+struct DocCli {
+    global: GlobalArgs,
+    command: Option<Command>,
+    // Discovered when the menu, help or an external subcommand needs them
+    plugins: OnceCell<Vec<Plugin>>,
+}
+
+impl DocCli {
+    // Create a new DocCli instance
+    fn new(cli: Cli) -> Self {
+        Self {
+            global: cli.global,
+            command: cli.command,
+            plugins: OnceCell::new(),
+        }
+    }
+
+    // Plugins found for the project, or on PATH outside of one
+    fn plugins(&self) -> &[Plugin] {
+        self.plugins.get_or_init(|| {
+            let project = Project::resolve(self.global.project_root.as_deref()).ok();
+            plugins::discover(project.as_ref().map(Project::root))
+        })
+    }
+
+    // Main execution method
+    fn run(&mut self) {
+        style::init(self.global.color);
+
+        // Completions are meant to be piped into a file, so skip the header
+        if let Some(Command::Completions { shell }) = &self.command {
+            self.exit_on_error("completions", commands::completions(*shell));
+            return;
+        }
+
+        // -q leaves only warnings, errors and the prompts themselves, JSON
+        // output keeps stdout for the result document, plugins print their
+        // own output and serve's output is its request log
+        let bare = matches!(self.command, Some(Command::External(_) | Command::Serve(_)));
+        if !self.global.quiet && !self.global.output.is_json() && !bare {
+            self.print_header();
+        }
+
+        // `self rebuild` is the fix, so there is no point warning before it
+        if !matches!(self.command, Some(Command::SelfCommand { .. })) {
+            rebuild::warn_if_stale();
+        }
+
+        match self.command.take() {
+            Some(command) => self.handle_command(&command),
+            None if io::stdin().is_terminal() && io::stdout().is_terminal() => self.run_menu(),
+            None => {
+                self.print_menu();
+                self.handle_user_choice();
+            }
+        }
+    }
+
+    // Show the full-screen menu and run what was picked
+    fn run_menu(&self) {
+        match tui::run(&self.context("menu"), self.plugins()) {
+            Ok(MenuOutcome::Run(command)) => self.handle_command(&command),
+            Ok(MenuOutcome::Help) => self.show_help(),
+            Ok(MenuOutcome::Quit) => {}
+            Err(e) => self.exit_on_error("menu", Err(e)),
+        }
+    }
+
+    // Print header with tool name
+    fn print_header(&self) {
+        say!("\n{}", "=".repeat(60));
+        say!("{} MkDocs Documentation CLI Tool", style::icon("📚", "[docs]"));
+        say!("{}", "=".repeat(60));
+    }
+
+    // Print the main menu when stdin or stdout is not a terminal
+    fn print_menu(&self) {
+        say!("\nAvailable commands:");
+        say!("  1. startup       - Start the development environment");
+        say!("  2. bump-version  - Bump the documentation version");
+        say!("  3. deploy        - Deploy all versions to GitHub Pages");
+        for (index, plugin) in self.plugins().iter().enumerate() {
+            say!("  {}. {:<13} - Plugin {}", index + 4, plugin.name, plugin.path.display());
+        }
+        say!("  h. help          - Show command help information");
+        say!();
+        prompt!("Enter your choice (1-{} or h) or command name: ", self.plugins().len() + 3);
+    }
+
+    // Handle user choice from the menu
+    fn handle_user_choice(&mut self) {
+        loop {
+            let mut choice = String::new();
+            match io::stdin().read_line(&mut choice) {
+                Ok(0) => return,
+                Ok(_) => {}
+                Err(e) => return self.exit_on_error("menu", Err(DocToolsError::io("Failed to read menu choice", e))),
+            }
+
+            let command = match choice.trim() {
+                "1" | "startup" => Command::Startup(StartupArgs::default()),
+                "2" | "bump-version" => Command::BumpVersion(BumpVersionArgs::default()),
+                "3" | "deploy" => Command::Deploy(DeployArgs::default()),
+                "h" | "help" => {
+                    self.show_help();
+                    return;
+                }
+                other => match self.plugin_choice(other) {
+                    Some(name) => Command::External(vec![name]),
+                    None => {
+                        say!("Invalid choice: {}. Please try again.", other);
+                        self.print_menu();
+                        continue;
+                    }
+                },
+            };
+
+            self.handle_command(&command);
+            return;
+        }
+    }
+
+    // Name of the plugin picked by number or name in the text menu
+    fn plugin_choice(&self, choice: &str) -> Option<String> {
+        let by_number = choice
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(4))
+            .and_then(|index| self.plugins().get(index));
+
+        by_number
+            .or_else(|| self.plugins().iter().find(|plugin| plugin.name == choice))
+            .map(|plugin| plugin.name.clone())
+    }
+
+    // Handle a specific command
+    fn handle_command(&self, command: &Command) {
+        match command {
+            Command::Startup(args) => self.run_startup(args),
+            Command::Serve(args) => self.exit_on_error("serve", commands::serve(&self.global, args)),
+            Command::BumpVersion(args) => self.run_bump_version(args),
+            Command::Deploy(args) => self.run_deploy_all_versions(args),
+            Command::Status => self.exit_on_error("status", commands::status(&self.context("status"))),
+            Command::Run(args) => self.exit_on_error("run", commands::run(&self.context("run"), args)),
+            Command::Doctor => self.run_doctor(),
+            Command::Config { action } => self.exit_on_error("config", commands::config(&self.context("config"), *action)),
+            Command::SelfCommand { action } => self.exit_on_error("self", commands::self_command(&self.global, *action)),
+            Command::Completions { shell } => self.exit_on_error("completions", commands::completions(*shell)),
+            Command::External(args) => self.run_plugin(args),
+        }
+    }
+
+    // Show help information generated from the command definitions
+    fn show_help(&self) {
+        println!();
+        if let Err(e) = cli::command_with_plugins(self.plugins()).print_long_help() {
+            eprintln!("Failed to print help: {}", e);
+        }
+    }
+
+    // Find the project every command operates on and load its configuration
+    fn context(&self, name: &str) -> Context {
+        match Context::load(&self.global) {
+            Ok(ctx) => ctx,
+            Err(e) => {
+                if self.global.output.is_json() {
+                    output::emit_error(name, &e);
+                } else {
+                    eprintln!("Error: {}", e);
+                }
+                std::process::exit(e.exit_code());
+            }
+        }
+    }
+
+    // Report a failed command and exit with its code
+    fn exit_on_error(&self, name: &str, result: doc_tools::Result<()>) {
+        if let Err(e) = result {
+            if self.global.output.is_json() {
+                output::emit_error(name, &e);
+            } else {
+                eprintln!("Error: {} failed: {}", name, e);
+            }
+            std::process::exit(e.exit_code());
+        }
+    }
+
+    // Run a doc-cli-<name> plugin, reporting errors under the plugin's name
+    fn run_plugin(&self, args: &[String]) {
+        let Some((name, args)) = args.split_first() else {
+            return;
+        };
+
+        // A typo should get its suggestion even outside of a project
+        let result = match self.plugins().iter().find(|plugin| plugin.name == *name) {
+            Some(plugin) => commands::plugin(&self.context(name), plugin, args),
+            None => Err(plugins::unknown_command(name, self.plugins())),
+        };
+
+        if let Err(e) = result {
+            if self.global.output.is_json() {
+                output::emit_error(name, &e);
+            } else {
+                eprintln!("Error: {}", e);
+            }
+            std::process::exit(e.exit_code());
+        }
+    }
+
+    // Run the environment checks, exiting with 1 if any failed
+    fn run_doctor(&self) {
+        let ctx = self.context("doctor");
+        info!("\n{} Running doctor...\n", style::icon("🩺", ">>"));
+
+        match commands::doctor(&ctx) {
+            Ok(true) => {}
+            Ok(false) => std::process::exit(1),
+            Err(e) => self.exit_on_error("doctor", Err(e)),
+        }
+    }
+
+    // Execute the startup functionality
+    fn run_startup(&self, args: &StartupArgs) {
+        let ctx = self.context("startup");
+        info!("\n{} Running startup...\n", style::icon("🚀", ">>"));
+
+        self.exit_on_error("startup", commands::startup(&ctx, args));
+    }
+
+    // Execute the bump-version functionality
+    fn run_bump_version(&self, args: &BumpVersionArgs) {
+        let ctx = self.context("bump-version");
+        info!("\n{} Running version bump...\n", style::icon("🔄", ">>"));
+
+        self.exit_on_error("bump-version", commands::bump_version(&ctx, args));
+    }
+
+    // Execute the deploy-all-versions functionality
+    fn run_deploy_all_versions(&self, args: &DeployArgs) {
+        let ctx = self.context("deploy");
+        info!("\n{} Running deploy-all-versions...\n", style::icon("🚀", ">>"));
+
+        self.exit_on_error("deploy", commands::deploy(&ctx, args));
+    }
+}
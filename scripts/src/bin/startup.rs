@@ -0,0 +1,21 @@
+use std::process;
+
+use clap::Parser;
+use doc_tools::cli::StartupCli;
+use doc_tools::{commands, output, Context};
+
+// Main entry point
+fn main() {
+    let cli = StartupCli::parse();
+
+    let result = Context::load(&cli.global).and_then(|ctx| commands::startup(&ctx, &cli.args));
+
+    if let Err(e) = result {
+        if cli.global.output.is_json() {
+            output::emit_error("startup", &e);
+        } else {
+            eprintln!("Error: {}", e);
+        }
+        process::exit(e.exit_code());
+    }
+}
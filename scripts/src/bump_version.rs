@@ -0,0 +1,377 @@
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::Arc;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::context::Context;
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::log::{self, Phase};
+use crate::project::Project;
+use crate::style::{BLUE, GREEN, NC, YELLOW};
+use crate::venv;
+use crate::{info, prompt, say, warn};
+
+// Which part of the version number to increment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BumpType {
+    Major,
+    Minor,
+    Patch,
+}
+
+// How a freshly tagged version is deployed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DeployTarget {
+    // Leave deployment for later
+    None,
+    // Deploy as a regular version
+    Version,
+    // Deploy and point the `latest` alias at it
+    Latest,
+}
+
+// What a version bump did, also the `--output json` result of `bump-version`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BumpReport {
+    pub previous_version: String,
+    pub new_version: String,
+    pub tag: String,
+    pub deployment: DeployTarget,
+}
+
+// Answers given up front instead of at the prompts
+//
+// Anything left as None is asked for interactively, which fails when stdin
+// is not a terminal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BumpOptions {
+    pub bump: Option<BumpType>,
+    // Exact version to tag instead of bumping, e.g. "2.0.0"
+    pub set_version: Option<String>,
+    // Skip the confirmation prompt
+    pub yes: bool,
+    pub deploy: Option<DeployTarget>,
+}
+
+pub struct VersionBumper {
+    project: Project,
+    config: Config,
+    runner: Arc<dyn CommandRunner>,
+    options: BumpOptions,
+    current_version: String,
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl VersionBumper {
+    pub fn new(ctx: &Context, options: BumpOptions) -> Self {
+        let current_version = Self::get_latest_tag(ctx.runner.as_ref(), ctx.project.root(), &ctx.config.tag_prefix.value);
+        let (major, minor, patch) = Self::parse_version(&current_version);
+
+        Self {
+            project: ctx.project.clone(),
+            config: ctx.config.clone(),
+            runner: Arc::clone(&ctx.runner),
+            options,
+            current_version,
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    pub fn run(&self) -> Result<BumpReport> {
+        say!("{}MkDocs Version Bumper{}", BLUE, NC);
+        say!("==============================");
+
+        say!("{}Current version:{} {}", YELLOW, NC, self.current_version);
+
+        // Use the requested version or bump type, asking only if neither was given
+        let new_version = match (&self.options.set_version, self.options.bump) {
+            (Some(version), _) => self.parse_set_version(version)?,
+            (None, Some(bump_type)) => self.calculate_new_version(bump_type),
+            (None, None) => self.calculate_new_version(self.prompt_bump_type()?),
+        };
+        say!("{}New version will be:{} {}", YELLOW, NC, new_version);
+
+        // Make sure the deployment question can be answered before tagging
+        if self.options.deploy.is_none() {
+            require_terminal(DEPLOY_INPUT)?;
+        }
+
+        // Confirm with user
+        if !self.options.yes && !self.confirm_version()? {
+            return Err(DocToolsError::UserAbort("Version bump canceled.".to_string()));
+        }
+
+        // Create and push git tag
+        self.create_git_tag(&new_version)?;
+
+        // Ask about deployment unless --deploy was given
+        let deployment = match self.options.deploy {
+            Some(target) => target,
+            None => self.prompt_deployment()?,
+        };
+        self.handle_deployment(&new_version, deployment)?;
+
+        info!("{}Version bump to {} complete!{}", GREEN, new_version, NC);
+
+        if deployment == DeployTarget::None {
+            info!("{}Note:{} You can deploy this version later using:", YELLOW, NC);
+            info!("  {}doc-cli deploy{} (to deploy all versions)", BLUE, NC);
+            info!("  or");
+            info!(
+                "  {}mike deploy {} --branch {} --push{} (to deploy just this version)",
+                BLUE,
+                self.config.tag_name(&new_version),
+                self.config.gh_pages_branch.value,
+                NC
+            );
+        }
+
+        Ok(BumpReport {
+            previous_version: self.current_version.clone(),
+            tag: self.config.tag_name(&new_version),
+            new_version,
+            deployment,
+        })
+    }
+
+    // Current version without the tag prefix
+    pub fn current_version(&self) -> &str {
+        &self.current_version
+    }
+
+    pub fn get_latest_tag(runner: &dyn CommandRunner, project_root: &Path, tag_prefix: &str) -> String {
+        // Get the latest tag from git or use 0.0.0 if none exists
+        let describe = Cmd::new("git", project_root)
+            .args(["describe", "--tags", "--abbrev=0"])
+            .capture();
+
+        match runner.run(&describe) {
+            Ok(output) if output.success() => {
+                let tag = output.stdout.trim().to_string();
+                // Remove the tag prefix (usually 'v') if present
+                match tag.strip_prefix(tag_prefix) {
+                    Some(stripped) => stripped.to_string(),
+                    None => tag,
+                }
+            }
+            _ => "0.0.0".to_string(),
+        }
+    }
+
+    pub fn parse_version(version: &str) -> (u32, u32, u32) {
+        let parts: Vec<&str> = version.split('.').collect();
+
+        let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
+        let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+        (major, minor, patch)
+    }
+
+    // Validate a version given with --set, accepting an optional tag prefix
+    pub fn parse_set_version(&self, version: &str) -> Result<String> {
+        let number = version.strip_prefix(self.config.tag_prefix.value.as_str()).unwrap_or(version);
+        let parts: Option<Vec<u32>> = number.split('.').map(|part| part.parse().ok()).collect();
+
+        let Some(&[major, minor, patch]) = parts.as_deref() else {
+            return Err(DocToolsError::Usage(format!(
+                "invalid version '{}', expected MAJOR.MINOR.PATCH such as 1.4.0",
+                version
+            )));
+        };
+
+        if (major, minor, patch) <= (self.major, self.minor, self.patch) {
+            warn!(
+                "{}Warning: {}.{}.{} is not newer than the current version {}{}",
+                YELLOW, major, minor, patch, self.current_version, NC
+            );
+        }
+        Ok(format!("{}.{}.{}", major, minor, patch))
+    }
+
+    fn prompt_bump_type(&self) -> Result<BumpType> {
+        require_terminal("a bump type (pass --major, --minor, --patch or --set VERSION)")?;
+
+        say!("What kind of version bump do you want to make?");
+        say!("1) Major ({}.0.0)", self.major + 1);
+        say!("2) Minor ({}.{}.0)", self.major, self.minor + 1);
+        say!("3) Patch ({}.{}.{})", self.major, self.minor, self.patch + 1);
+
+        Ok(match prompt_choice()? {
+            1 => BumpType::Major,
+            2 => BumpType::Minor,
+            _ => BumpType::Patch,
+        })
+    }
+
+    pub fn calculate_new_version(&self, bump_type: BumpType) -> String {
+        match bump_type {
+            BumpType::Major => format!("{}.0.0", self.major + 1),
+            BumpType::Minor => format!("{}.{}.0", self.major, self.minor + 1),
+            BumpType::Patch => format!("{}.{}.{}", self.major, self.minor, self.patch + 1),
+        }
+    }
+
+    fn confirm_version(&self) -> Result<bool> {
+        require_terminal("confirmation (pass --yes)")?;
+
+        prompt!("Proceed with this version? (y/n): ");
+
+        let mut confirm = String::new();
+        io::stdin()
+            .read_line(&mut confirm)
+            .map_err(|e| DocToolsError::io("Failed to read input", e))?;
+
+        Ok(confirm.trim().eq_ignore_ascii_case("y"))
+    }
+
+    pub fn create_git_tag(&self, new_version: &str) -> Result<()> {
+        log::set_phase(Phase::Tag);
+        let tag_name = self.config.tag_name(new_version);
+        info!("Creating new Git tag {}...", tag_name);
+
+        let tag_message = format!("Version {}", new_version);
+
+        let tag = self.git().args(["tag", "-a", &tag_name, "-m", &tag_message]).mutating();
+
+        if !self.runner.run(&tag)?.success() {
+            return Err(DocToolsError::Git(format!("Failed to create git tag {}.", tag_name)));
+        }
+
+        log::set_phase(Phase::Push);
+        info!("Pushing tag to remote...");
+
+        let push = self.git().args(["push", &self.config.remote.value, &tag_name]).mutating();
+
+        if !self.runner.run(&push)?.success() {
+            return Err(DocToolsError::Git(format!("Failed to push git tag {} to remote.", tag_name)));
+        }
+        Ok(())
+    }
+
+    fn prompt_deployment(&self) -> Result<DeployTarget> {
+        require_terminal(DEPLOY_INPUT)?;
+
+        say!(
+            "\n{}Do you want to deploy this version to {} with mike?{}",
+            YELLOW, self.config.gh_pages_branch.value, NC
+        );
+        say!("1) Yes, deploy as a regular version");
+        say!("2) Yes, deploy as a regular version AND set as 'latest'");
+        say!("3) No, skip deployment");
+
+        Ok(match prompt_choice()? {
+            1 => DeployTarget::Version,
+            2 => DeployTarget::Latest,
+            _ => DeployTarget::None,
+        })
+    }
+
+    pub fn handle_deployment(&self, new_version: &str, target: DeployTarget) -> Result<()> {
+        let tag_name = self.config.tag_name(new_version);
+        let branch = self.config.gh_pages_branch.value.as_str();
+        let remote = self.config.remote.value.as_str();
+        log::set_phase(Phase::Deploy);
+
+        match target {
+            DeployTarget::Version => {
+                info!("{}Deploying {} to {}...{}", BLUE, tag_name, branch, NC);
+
+                self.run_mike(
+                    &["deploy", &tag_name, "--branch", branch, "--remote", remote, "--push"],
+                    "Failed to deploy version.",
+                )?;
+                info!("{}Deployment complete!{}", GREEN, NC);
+            }
+            DeployTarget::Latest => {
+                info!("{}Deploying {} to {} and setting as 'latest'...{}", BLUE, tag_name, branch, NC);
+
+                // First deploy the version
+                self.run_mike(&["deploy", &tag_name, "--branch", branch], "Failed to deploy version.")?;
+
+                // Then set it as latest
+                log::set_phase(Phase::Alias);
+                self.run_mike(
+                    &["deploy", &tag_name, "latest", "--branch", branch, "--update-aliases"],
+                    "Failed to set version as latest.",
+                )?;
+
+                // Set default to latest
+                self.run_mike(&["set-default", "latest", "--branch", branch], "Failed to set default version.")?;
+
+                // Push changes
+                log::set_phase(Phase::Push);
+                let push = self.git().args(["push", remote, branch]).mutating();
+
+                if !self.runner.run(&push)?.success() {
+                    return Err(DocToolsError::Git(format!("Failed to push {} branch.", branch)));
+                }
+                info!("{}Deployment complete and set as 'latest'!{}", GREEN, NC);
+            }
+            DeployTarget::None => info!("Skipping deployment to {}.", branch),
+        }
+        Ok(())
+    }
+
+    // Git command run from the project root
+    fn git(&self) -> Cmd {
+        Cmd::new("git", self.project.root())
+    }
+
+    // Run a mike command, failing with the given message if it does not succeed
+    fn run_mike(&self, args: &[&str], failure: &str) -> Result<()> {
+        let mike = Cmd::new(venv::program(&self.project, "mike"), self.project.root())
+            .args(args.iter().copied())
+            .mutating();
+
+        if !self.runner.run(&mike)?.success() {
+            return Err(DocToolsError::Mike(failure.to_string()));
+        }
+        Ok(())
+    }
+}
+
+// Input the deployment prompt needs, for the non-terminal error message
+const DEPLOY_INPUT: &str = "a deployment choice (pass --deploy none, version or latest)";
+
+// Fail instead of waiting for an answer nobody can give
+fn require_terminal(missing: &str) -> Result<()> {
+    if io::stdin().is_terminal() {
+        return Ok(());
+    }
+    Err(DocToolsError::Usage(format!("stdin is not a terminal, so {} is required", missing)))
+}
+
+// Read a 1-3 menu choice from stdin, asking again until it is valid
+fn prompt_choice() -> Result<u8> {
+    loop {
+        prompt!("Enter choice [1-3]: ");
+
+        let mut choice = String::new();
+        let read = io::stdin()
+            .read_line(&mut choice)
+            .map_err(|e| DocToolsError::io("Failed to read input", e))?;
+
+        // End of input means nobody is there to answer
+        if read == 0 {
+            return Err(DocToolsError::UserAbort("No input available.".to_string()));
+        }
+
+        match choice.trim() {
+            "1" => return Ok(1),
+            "2" => return Ok(2),
+            "3" => return Ok(3),
+            _ => say!("Invalid option. Please enter 1, 2, or 3."),
+        }
+    }
+}
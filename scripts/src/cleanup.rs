@@ -0,0 +1,231 @@
+// Cleanup that must happen however a command ends
+//
+// Guards such as BranchGuard register an action while they are alive. On a
+// normal return, an error or a panic the guard's Drop runs it; on SIGINT,
+// SIGTERM or SIGHUP the handler installed by `install_signal_handler` runs
+// every pending action and exits with 128 + the signal number.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::{debug, warn};
+
+type Action = Box<dyn FnOnce() + Send>;
+
+static PENDING: Mutex<Vec<(u64, Action)>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+// Set once a signal arrived; the handler owns the cleanup from then on
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INSTALL: Once = Once::new();
+
+fn pending() -> MutexGuard<'static, Vec<(u64, Action)>> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Run pending cleanup on SIGINT, SIGTERM and SIGHUP; safe to call repeatedly
+pub fn install_signal_handler() {
+    INSTALL.call_once(|| {
+        #[cfg(unix)]
+        {
+            use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM};
+            use signal_hook::iterator::Signals;
+
+            match Signals::new([SIGINT, SIGTERM, SIGHUP]) {
+                Ok(mut signals) => {
+                    thread::spawn(move || {
+                        if let Some(signal) = signals.forever().next() {
+                            INTERRUPTED.store(true, Ordering::SeqCst);
+                            debug!("Received signal {}, cleaning up", signal);
+                            run_pending();
+                            std::process::exit(128 + signal);
+                        }
+                    });
+                }
+                Err(e) => warn!("Could not install signal handlers: {}", e),
+            }
+        }
+    });
+}
+
+// Run every pending action, newest first
+fn run_pending() {
+    let actions: Vec<(u64, Action)> = pending().drain(..).collect();
+    for (_, action) in actions.into_iter().rev() {
+        action();
+    }
+}
+
+// After a signal the handler finishes the cleanup and exits the process, so
+// the interrupted thread must not race it
+fn wait_if_interrupted() {
+    if INTERRUPTED.load(Ordering::SeqCst) {
+        loop {
+            thread::park();
+        }
+    }
+}
+
+// An action registered to run on exit; dropping it discards the action
+#[derive(Debug)]
+pub struct Registration {
+    id: u64,
+}
+
+// Register `action` to run if the process is interrupted
+pub fn register(action: impl FnOnce() + Send + 'static) -> Registration {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    pending().push((id, Box::new(action)));
+    Registration { id }
+}
+
+impl Registration {
+    // Run the action now, unless a signal handler already did
+    pub fn run(self) {
+        if let Some(action) = self.take() {
+            action();
+        }
+    }
+
+    fn take(&self) -> Option<Action> {
+        wait_if_interrupted();
+        let mut pending = pending();
+        let index = pending.iter().position(|(id, _)| *id == self.id)?;
+        Some(pending.remove(index).1)
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        drop(self.take());
+    }
+}
+
+// Switches back to the original branch, or commit for a detached HEAD, when
+// dropped or interrupted
+#[derive(Debug)]
+pub struct BranchGuard {
+    runner: Arc<dyn CommandRunner>,
+    project_root: PathBuf,
+    original: String,
+    registration: Option<Registration>,
+}
+
+impl BranchGuard {
+    // Check out `target`; `current` is what `git rev-parse --abbrev-ref HEAD`
+    // reported, "HEAD" when detached
+    pub fn checkout(runner: Arc<dyn CommandRunner>, project_root: &Path, current: &str, target: &str) -> Result<Self> {
+        let original = if current == "HEAD" {
+            let output = runner.run(&Cmd::new("git", project_root).args(["rev-parse", "HEAD"]).capture())?;
+            if !output.success() {
+                return Err(DocToolsError::Git("Failed to resolve the detached HEAD.".to_string()));
+            }
+            output.stdout.trim().to_string()
+        } else {
+            current.to_string()
+        };
+
+        let checkout = Cmd::new("git", project_root).args(["checkout", target]).mutating();
+        if !runner.run(&checkout)?.success() {
+            return Err(DocToolsError::Git(format!(
+                "Cannot switch to {} branch. Make sure it exists.",
+                target
+            )));
+        }
+
+        let registration = {
+            let (runner, project_root, original) = (Arc::clone(&runner), project_root.to_path_buf(), original.clone());
+            register(move || {
+                if switch_back(runner.as_ref(), &project_root, &original).is_err() {
+                    warn!("Could not switch back to {}; run 'git checkout {}' yourself.", original, original);
+                }
+            })
+        };
+
+        Ok(Self {
+            runner,
+            project_root: project_root.to_path_buf(),
+            original,
+            registration: Some(registration),
+        })
+    }
+
+    // Branch name or commit restored when the guard ends
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    // Switch back now and report whether it worked
+    pub fn restore(mut self) -> Result<()> {
+        if let Some(registration) = self.registration.take() {
+            drop(registration);
+            switch_back(self.runner.as_ref(), &self.project_root, &self.original)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for BranchGuard {
+    fn drop(&mut self) {
+        if let Some(registration) = self.registration.take() {
+            registration.run();
+        }
+    }
+}
+
+// git checkout, retried briefly in case an interrupted git still holds the index lock
+fn switch_back(runner: &dyn CommandRunner, project_root: &Path, original: &str) -> Result<()> {
+    let checkout = Cmd::new("git", project_root).args(["checkout", original]).mutating();
+
+    for attempt in 1..=3 {
+        if runner.run(&checkout)?.success() {
+            return Ok(());
+        }
+        debug!("Switching back to {} failed (attempt {})", original, attempt);
+        if attempt < 3 {
+            thread::sleep(Duration::from_millis(200));
+        }
+    }
+    Err(DocToolsError::Git("Failed to switch back to original branch.".to_string()))
+}
+
+// Terminates a background process when dropped or interrupted
+#[derive(Debug)]
+pub struct ChildGuard {
+    registration: Option<Registration>,
+}
+
+impl ChildGuard {
+    pub fn new(runner: Arc<dyn CommandRunner>, project_root: &Path, pid: u32) -> Self {
+        let project_root = project_root.to_path_buf();
+        let registration = register(move || {
+            debug!("Terminating process {}", pid);
+            let kill = Cmd::new("kill", &project_root)
+                .args(["-TERM", &pid.to_string()])
+                .quiet()
+                .mutating();
+            let _ = runner.run(&kill);
+        });
+
+        Self {
+            registration: Some(registration),
+        }
+    }
+
+    // The process exited by itself, so there is nothing left to terminate
+    pub fn disarm(mut self) {
+        self.registration.take();
+    }
+}
+
+impl Drop for ChildGuard {
+    fn drop(&mut self) {
+        if let Some(registration) = self.registration.take() {
+            registration.run();
+        }
+    }
+}
@@ -0,0 +1,329 @@
+// Command line definitions shared by doc-cli and the standalone binaries
+//
+// Help output, typo suggestions and shell completions are all generated
+// from these types, so they always match what the parsers accept.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use crate::bump_version::{BumpType, DeployTarget};
+use crate::log::Level;
+use crate::output::OutputFormat;
+use crate::plugins::Plugin;
+use crate::port::PortSpec;
+use crate::style::ColorChoice;
+
+const EXAMPLES: &str = "\
+Examples:
+  doc-cli                                  # Start interactive menu
+  doc-cli startup                          # Start development server
+  doc-cli startup --draft-version 1.2.0    # Start server with draft version 1.2.0
+  doc-cli startup --port auto              # Start server on the first free port from 8000
+  doc-cli startup --port 8001 --bind 0.0.0.0
+                                           # Serve on port 8001 to the whole network
+  doc-cli serve site --base-path 1.2.0     # Serve a built site/ under /1.2.0/
+  doc-cli bump-version                     # Bump the version
+  doc-cli bump-version --minor --yes --deploy latest
+                                           # Release a minor version from CI
+  doc-cli deploy                           # Deploy all versions
+  doc-cli deploy --force                   # Redeploy every version
+  doc-cli deploy --force --dry-run         # Show what a forced deploy would run
+  doc-cli -v deploy --log-file deploy.log  # Show debug output and keep a log
+  doc-cli status                           # Show what is tagged, deployed and running
+  doc-cli run release.toml                 # Run the release workflow step by step
+  doc-cli run release --var prerelease=true
+                                           # Run it for a pre-release
+  doc-cli doctor                           # Check the environment before a run
+  doc-cli config show                      # Print the effective configuration
+  doc-cli deploy -n --output json          # Print the deploy result as JSON
+  doc-cli -c server.port=8001 startup      # Override a doc-tools.toml value
+  doc-cli self rebuild                     # Rebuild doc-cli and the other tools
+  doc-cli completions bash                 # Print bash completions";
+
+// doc-cli's command definition with the discovered plugins listed in its help
+pub fn command_with_plugins(plugins: &[Plugin]) -> clap::Command {
+    if plugins.is_empty() {
+        return Cli::command();
+    }
+
+    let width = plugins.iter().map(|plugin| plugin.name.len()).max().unwrap_or(0);
+    let mut help = format!("{}\n\nPlugins:", EXAMPLES);
+    for plugin in plugins {
+        help.push_str(&format!("\n  doc-cli {:<width$}  # {}", plugin.name, plugin.path.display(), width = width));
+    }
+    Cli::command().after_help(help)
+}
+
+/// MkDocs Documentation CLI Tool
+#[derive(Debug, Parser)]
+#[command(name = "doc-cli", version, after_help = EXAMPLES)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+// Options accepted by every tool and subcommand
+#[derive(Debug, Clone, Default, Args)]
+pub struct GlobalArgs {
+    /// Use DIR as the project root instead of discovering it from the current directory
+    #[arg(short = 'C', long, global = true, value_name = "DIR")]
+    pub project_root: Option<PathBuf>,
+
+    /// Read configuration from FILE instead of <project root>/doc-tools.toml
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config_file: Option<PathBuf>,
+
+    /// Override a configuration value, e.g. -c server.port=8001
+    #[arg(short = 'c', long = "config", global = true, value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Print every git, mike, pip and kill invocation without running the mutating ones
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Only print warnings and errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Print debug output; repeat (-vv) to also trace every command run
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Append every log record, including debug output, to FILE
+    #[arg(long, global = true, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// Color output: auto (terminals only, honoring NO_COLOR), always or never
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto, value_name = "WHEN")]
+    pub color: ColorChoice,
+
+    /// Report results as text for people or as one JSON document per command
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text, value_name = "FORMAT")]
+    pub output: OutputFormat,
+}
+
+impl GlobalArgs {
+    // Terminal log level selected by -q and -v
+    pub fn log_level(&self) -> Level {
+        Level::from_flags(self.quiet, self.verbose)
+    }
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Start the documentation development environment
+    ///
+    /// Sets up MkDocs with mike for versioned documentation.
+    Startup(StartupArgs),
+    /// Serve a built site directory with the built-in static file server
+    ///
+    /// Logs every request; startup uses it to preview draft versions.
+    Serve(ServeArgs),
+    /// Bump the documentation version
+    ///
+    /// Creates a new git tag and optionally deploys it.
+    BumpVersion(BumpVersionArgs),
+    /// Deploy all versions to GitHub Pages
+    ///
+    /// Uses mike to deploy to the gh-pages branch.
+    #[command(alias = "deploy-all-versions")]
+    Deploy(DeployArgs),
+    /// Show tags, deployed versions, versions.json drift and the server state
+    Status,
+    /// Run the steps of a workflow file such as release.toml in order
+    ///
+    /// Stops at the first failing step and prints a summary of every step.
+    Run(RunArgs),
+    /// Check the tools, packages and repository setup the commands need
+    ///
+    /// Exits with 1 if any check fails; warnings do not affect the exit code.
+    Doctor,
+    /// Inspect the doc-tools.toml configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Manage the doc-tools binaries themselves
+    #[command(name = "self")]
+    SelfCommand {
+        #[command(subcommand)]
+        action: SelfAction,
+    },
+    /// Print shell completions for doc-cli
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+    // Any other subcommand runs the doc-cli-<name> plugin, name first
+    #[command(external_subcommand)]
+    External(Vec<String>),
+}
+
+impl Command {
+    // Name of the subcommand as typed on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Startup(_) => "startup",
+            Self::Serve(_) => "serve",
+            Self::BumpVersion(_) => "bump-version",
+            Self::Deploy(_) => "deploy",
+            Self::Status => "status",
+            Self::Run(_) => "run",
+            Self::Doctor => "doctor",
+            Self::Config { .. } => "config",
+            Self::SelfCommand { .. } => "self",
+            Self::Completions { .. } => "completions",
+            Self::External(_) => "plugin",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum ConfigAction {
+    /// Print the effective configuration and where each value came from
+    Show,
+}
+
+#[derive(Debug, Clone, Copy, Subcommand)]
+pub enum SelfAction {
+    /// Rebuild the release binaries from the sources in scripts/
+    Rebuild {
+        /// Only rebuild when the sources changed since the last build
+        #[arg(long)]
+        if_stale: bool,
+    },
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct StartupArgs {
+    /// View a specific version not yet deployed to gh-pages
+    #[arg(long, value_name = "VERSION")]
+    pub draft_version: Option<String>,
+    /// Delete and recreate the project's .venv before installing dependencies
+    #[arg(long)]
+    pub recreate_venv: bool,
+    /// Run the pip installs even if requirements.txt and setup.py are unchanged
+    #[arg(long)]
+    pub reinstall: bool,
+    /// Serve on PORT instead of server.port; 'auto' picks the first free port from server.port up
+    #[arg(long, value_name = "PORT|auto")]
+    pub port: Option<PortSpec>,
+    /// Listen on this IP address instead of 127.0.0.1 (0.0.0.0 in Codespaces)
+    #[arg(long, value_name = "ADDR")]
+    pub bind: Option<IpAddr>,
+    /// Offer to kill whatever holds the port, not just mkdocs, mike and http servers
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ServeArgs {
+    /// Directory to serve [default: site/ in the project root]
+    #[arg(value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+    /// Listen on PORT instead of server.port; 'auto' picks the first free port from server.port up
+    #[arg(long, value_name = "PORT|auto")]
+    pub port: Option<PortSpec>,
+    /// Listen on this IP address instead of 127.0.0.1
+    #[arg(long, value_name = "ADDR")]
+    pub bind: Option<IpAddr>,
+    /// Serve the site under /PATH/ instead of the root, as mike deploys a version
+    #[arg(long, value_name = "PATH")]
+    pub base_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct BumpVersionArgs {
+    /// Bump the major version (x.0.0)
+    #[arg(long, group = "new_version")]
+    pub major: bool,
+    /// Bump the minor version (0.x.0)
+    #[arg(long, group = "new_version")]
+    pub minor: bool,
+    /// Bump the patch version (0.0.x)
+    #[arg(long, group = "new_version")]
+    pub patch: bool,
+    /// Tag exactly VERSION, e.g. 2.0.0, instead of bumping
+    #[arg(long = "set", value_name = "VERSION", group = "new_version")]
+    pub set_version: Option<String>,
+    /// Create the tag without asking for confirmation
+    #[arg(short, long)]
+    pub yes: bool,
+    /// Deploy the new version: none, version, or latest (also moves the 'latest' alias)
+    #[arg(long, value_enum, value_name = "TARGET")]
+    pub deploy: Option<DeployTarget>,
+}
+
+impl BumpVersionArgs {
+    // Bump type selected by --major, --minor or --patch
+    pub fn bump_type(&self) -> Option<BumpType> {
+        if self.major {
+            Some(BumpType::Major)
+        } else if self.minor {
+            Some(BumpType::Minor)
+        } else if self.patch {
+            Some(BumpType::Patch)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct RunArgs {
+    /// Workflow file, or the name of one in the project root
+    #[arg(value_name = "WORKFLOW", default_value = crate::workflow::DEFAULT_WORKFLOW)]
+    pub workflow: String,
+    /// Set a workflow variable, overriding its [vars] value
+    #[arg(long = "var", value_name = "NAME=VALUE")]
+    pub vars: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct DeployArgs {
+    /// Force deploy all versions (ignores existing deployments)
+    #[arg(short, long)]
+    pub force: bool,
+    /// Skip interactive prompts
+    #[arg(short, long)]
+    pub non_interactive: bool,
+}
+
+/// Start the documentation development environment
+#[derive(Debug, Parser)]
+#[command(name = "startup", version)]
+pub struct StartupCli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(flatten)]
+    pub args: StartupArgs,
+}
+
+/// Bump the documentation version
+#[derive(Debug, Parser)]
+#[command(name = "bump-version", version)]
+pub struct BumpVersionCli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(flatten)]
+    pub args: BumpVersionArgs,
+}
+
+/// Deploy all versions to GitHub Pages
+#[derive(Debug, Parser)]
+#[command(name = "deploy-all-versions", version)]
+pub struct DeployCli {
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    #[command(flatten)]
+    pub args: DeployArgs,
+}
@@ -0,0 +1,288 @@
+// Entry points shared by doc-cli and the standalone binaries
+//
+// Each function takes the parsed arguments for its subcommand, so
+// `doc-cli deploy -f` and `deploy-all-versions -f` run exactly the same code.
+
+use std::io::{self, Write};
+
+use clap::CommandFactory;
+use clap_complete::Shell;
+
+use crate::bump_version::BumpOptions;
+use crate::cli::{BumpVersionArgs, Cli, ConfigAction, DeployArgs, GlobalArgs, RunArgs, SelfAction, ServeArgs, StartupArgs};
+use crate::deploy::{self, Deployer};
+use crate::doctor::{Doctor, Outcome};
+use crate::error::{DocToolsError, Result};
+use crate::log;
+use crate::output;
+use crate::plugins::Plugin;
+use crate::port::{self, PortSpec};
+use crate::rebuild::Sources;
+use crate::startup::Environment;
+use crate::static_server::StaticServer;
+use crate::status::StatusReport;
+use crate::style::{GREEN, NC, RED, YELLOW};
+use crate::workflow::{StepStatus, Workflow};
+use crate::{Config, Context, Project, Startup, VersionBumper};
+
+// Run the startup tool
+pub fn startup(ctx: &Context, args: &StartupArgs) -> Result<()> {
+    log::set_command("startup");
+
+    if let Some(version) = &args.draft_version {
+        crate::info!("Using draft version: {}", version);
+    }
+
+    let startup = Startup::new(ctx, args.draft_version.clone())
+        .recreate_venv(args.recreate_venv)
+        .reinstall(args.reinstall)
+        .port(args.port)
+        .bind(args.bind.map(|addr| addr.to_string()))
+        .force(args.force);
+
+    // The result is reported as soon as the server is up, not when it exits
+    let server = startup.start()?;
+    output::emit(ctx.output, "startup", &server.report())?;
+    server.wait()
+}
+
+// Serve a built site until interrupted; a directory given on the command
+// line needs no project
+pub fn serve(global: &GlobalArgs, args: &ServeArgs) -> Result<()> {
+    Context::runtime(global)?;
+    log::set_command("serve");
+
+    let project = Project::resolve(global.project_root.as_deref());
+    let config = match &project {
+        Ok(project) => Config::load(project, global.config_file.as_deref(), &global.set)?,
+        Err(_) => Config::default(),
+    };
+    let dir = match &args.dir {
+        Some(dir) => dir.clone(),
+        None => project?.site_dir(),
+    };
+    if !dir.is_dir() {
+        return Err(DocToolsError::MissingFile(dir));
+    }
+
+    let bind = args
+        .bind
+        .map_or_else(|| Environment::Local.default_bind().to_string(), |addr| addr.to_string());
+    let port = match args.port.unwrap_or(PortSpec::Fixed(config.port.value)) {
+        PortSpec::Fixed(port) => port,
+        PortSpec::Auto => port::pick_free(&bind, config.port.value)?,
+    };
+
+    StaticServer::new(dir).base_path(args.base_path.as_deref()).listen(&bind, port)
+}
+
+// Run the version bump tool
+pub fn bump_version(ctx: &Context, args: &BumpVersionArgs) -> Result<()> {
+    log::set_command("bump-version");
+
+    let options = BumpOptions {
+        bump: args.bump_type(),
+        set_version: args.set_version.clone(),
+        yes: args.yes,
+        deploy: args.deploy,
+    };
+
+    let report = VersionBumper::new(ctx, options).run()?;
+    output::emit(ctx.output, "bump-version", &report)
+}
+
+// Run the deploy-all-versions tool
+pub fn deploy(ctx: &Context, args: &DeployArgs) -> Result<()> {
+    log::set_command("deploy");
+
+    // Always display the prompt selection before anything else
+    let force = if args.force || args.non_interactive {
+        args.force
+    } else {
+        deploy::select_deployment_mode()
+    };
+
+    let report = Deployer::new(ctx, force)?.run()?;
+    report.check()?;
+    output::emit(ctx.output, "deploy", &report)
+}
+
+// Show the release state of the documentation
+pub fn status(ctx: &Context) -> Result<()> {
+    log::set_command("status");
+
+    let report = StatusReport::gather(ctx);
+    let list = |items: &[String]| if items.is_empty() { "none".to_string() } else { items.join(", ") };
+
+    match (&report.latest_tag, report.commits_since_tag) {
+        (Some(tag), Some(commits)) => crate::info!("Latest tag      {} ({} commits since)", tag, commits),
+        (Some(tag), None) => crate::info!("Latest tag      {}", tag),
+        (None, _) => crate::info!("Latest tag      {}none{}", YELLOW, NC),
+    }
+    crate::info!("Deployed        {} on {}", list(&report.deployed_versions), report.deploy_branch);
+    crate::info!("'latest' alias  {}", report.latest_alias.as_deref().unwrap_or("none"));
+
+    if report.undeployed_tags.is_empty() {
+        crate::info!("Not deployed    {}none{}", GREEN, NC);
+    } else {
+        crate::info!("Not deployed    {}{}{}", YELLOW, list(&report.undeployed_tags), NC);
+    }
+
+    let drift = &report.versions_json;
+    if drift.in_sync() {
+        crate::info!("versions.json   {}in sync with {}{}", GREEN, report.deploy_branch, NC);
+    } else if !drift.local_found || !drift.deployed_found {
+        let missing = if drift.local_found { format!("{}:versions.json", report.deploy_branch) } else { "versions.json".to_string() };
+        crate::info!("versions.json   {}{} not found{}", YELLOW, missing, NC);
+    } else {
+        crate::info!("versions.json   {}differs from {}{}", YELLOW, report.deploy_branch, NC);
+        crate::info!("  only in versions.json:  {}", list(&drift.only_local));
+        crate::info!("  only on {}:  {}", report.deploy_branch, list(&drift.only_deployed));
+        crate::info!(
+            "  'latest':  {} locally, {} deployed",
+            drift.local_latest.as_deref().unwrap_or("none"),
+            drift.deployed_latest.as_deref().unwrap_or("none")
+        );
+    }
+
+    if report.server_listening {
+        crate::info!("Server          {}listening on port {}{}", GREEN, report.port, NC);
+    } else {
+        crate::info!("Server          not running (port {})", report.port);
+    }
+
+    output::emit(ctx.output, "status", &report)
+}
+
+// Run the steps of a workflow file and summarize how each one ended
+pub fn run(ctx: &Context, args: &RunArgs) -> Result<()> {
+    log::set_command("run");
+
+    let overrides = args
+        .vars
+        .iter()
+        .map(|entry| match entry.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.to_string())),
+            None => Err(DocToolsError::Usage(format!("expected NAME=VALUE, got '{}'", entry))),
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let path = Workflow::locate(&ctx.project, &args.workflow)?;
+    let workflow = Workflow::load(&path)?;
+    crate::debug!("Workflow file: {}", path.display());
+
+    let (report, result) = workflow.run(ctx, &overrides);
+
+    let width = report.steps.iter().map(|step| step.name.len()).max().unwrap_or(0);
+    crate::info!("\nSummary of {}:", report.workflow);
+    for (index, step) in report.steps.iter().enumerate() {
+        let color = match step.status {
+            StepStatus::Ok => GREEN,
+            StepStatus::Skipped | StepStatus::NotRun => YELLOW,
+            StepStatus::Failed => RED,
+        };
+        let detail = match (&step.detail, step.status) {
+            (Some(detail), _) => detail.clone(),
+            (None, StepStatus::Ok) => format!("{:.1}s", step.duration_ms as f64 / 1000.0),
+            (None, _) => String::new(),
+        };
+        let line = format!("{:<width$}  {}{:<8}{}  {}", step.name, color, step.status.to_string(), NC, detail, width = width);
+        crate::info!("  {:>2}. {}", index + 1, line.trim_end());
+    }
+
+    result?;
+    output::emit(ctx.output, "run", &report)
+}
+
+// Run every environment check; returns whether all of them passed or warned
+pub fn doctor(ctx: &Context) -> Result<bool> {
+    log::set_command("doctor");
+
+    let report = Doctor::new(ctx).run();
+    let width = report.checks.iter().map(|check| check.name.len()).max().unwrap_or(0);
+
+    // Problems stay visible with -q
+    for check in &report.checks {
+        let line = format!("{:<width$}  {}", check.name, check.detail, width = width);
+        let fix = check.hint.as_deref().unwrap_or_default();
+        match check.outcome {
+            Outcome::Pass => crate::info!("  {}PASS{}  {}", GREEN, NC, line),
+            Outcome::Warn => crate::warn!("  {}WARN{}  {}\n        {:<width$}  fix: {}", YELLOW, NC, line, "", fix, width = width),
+            Outcome::Fail => crate::error!("  {}FAIL{}  {}\n        {:<width$}  fix: {}", RED, NC, line, "", fix, width = width),
+        }
+    }
+    crate::info!(
+        "\n{} passed, {} warnings, {} failed",
+        report.passed,
+        report.warnings,
+        report.failures
+    );
+
+    output::emit(ctx.output, "doctor", &report)?;
+    Ok(report.healthy())
+}
+
+// Run a `doc-cli config` action
+pub fn config(ctx: &Context, action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Show if ctx.output.is_json() => {
+            let settings: serde_json::Map<String, serde_json::Value> = ctx
+                .config
+                .entries()
+                .into_iter()
+                .map(|(key, value, source)| {
+                    (key.to_string(), serde_json::json!({ "value": value, "source": source.to_string() }))
+                })
+                .collect();
+
+            output::emit(
+                ctx.output,
+                "config",
+                &serde_json::json!({ "project_root": ctx.project.root(), "settings": settings }),
+            )?;
+        }
+        ConfigAction::Show => {
+            let entries = ctx.config.entries();
+            let width = entries.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+
+            println!("# project root: {}", ctx.project.root().display());
+            for (key, value, source) in entries {
+                println!("{:<width$} = {:<12} # {}", key, value.to_string(), source, width = width);
+            }
+        }
+    }
+    Ok(())
+}
+
+// Run a `doc-cli self` action; works outside of any documentation project
+pub fn self_command(global: &GlobalArgs, action: SelfAction) -> Result<()> {
+    let runner = Context::runtime(global)?;
+    log::set_command("self");
+
+    match action {
+        SelfAction::Rebuild { if_stale } => {
+            let report = Sources::locate()?.rebuild(runner.as_ref(), !if_stale, global.dry_run)?;
+            output::emit(global.output, "self", &report)
+        }
+    }
+}
+
+// Run a discovered plugin with the arguments after its name
+pub fn plugin(ctx: &Context, plugin: &Plugin, args: &[String]) -> Result<()> {
+    log::set_command("plugin");
+
+    crate::debug!("Running plugin {}", plugin.path.display());
+    plugin.run(ctx, args)?;
+    output::emit(ctx.output, &plugin.name, &serde_json::json!({ "plugin": plugin.path }))
+}
+
+// Print shell completions for doc-cli to stdout
+pub fn completions(shell: Shell) -> Result<()> {
+    // Render into a buffer first so a closed pipe is reported instead of panicking
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "doc-cli", &mut script);
+
+    io::stdout()
+        .write_all(&script)
+        .map_err(|e| DocToolsError::io("Failed to write completions", e))
+}
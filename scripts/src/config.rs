@@ -0,0 +1,255 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{DocToolsError, Result};
+use crate::project::Project;
+
+// Name of the configuration file looked up in the project root
+pub const CONFIG_FILE_NAME: &str = "doc-tools.toml";
+
+// Where the effective value of a setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File(path) => write!(f, "file {}", path.display()),
+            Self::Env(name) => write!(f, "env {}", name),
+            Self::Cli => write!(f, "command line"),
+        }
+    }
+}
+
+// A configuration value together with the layer that provided it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+// A known configuration key and how it is spelled in each layer
+struct Key {
+    name: &'static str,
+    env: &'static str,
+    default: &'static str,
+}
+
+const SERVER_PORT: Key = Key { name: "server.port", env: "DOC_TOOLS_SERVER_PORT", default: "8000" };
+const DEPLOY_REMOTE: Key = Key { name: "deploy.remote", env: "DOC_TOOLS_DEPLOY_REMOTE", default: "origin" };
+const DEPLOY_BRANCH: Key = Key { name: "deploy.branch", env: "DOC_TOOLS_DEPLOY_BRANCH", default: "gh-pages" };
+const TAG_PREFIX: Key = Key { name: "version.tag_prefix", env: "DOC_TOOLS_VERSION_TAG_PREFIX", default: "v" };
+const PYTHON: Key = Key { name: "python.executable", env: "DOC_TOOLS_PYTHON_EXECUTABLE", default: "python" };
+
+const KEYS: &[&Key] = &[&SERVER_PORT, &DEPLOY_REMOTE, &DEPLOY_BRANCH, &TAG_PREFIX, &PYTHON];
+
+// Effective configuration shared by every subcommand
+//
+// Each value is resolved with the precedence: command line, environment,
+// doc-tools.toml, built-in default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub port: Setting<u16>,
+    pub remote: Setting<String>,
+    pub gh_pages_branch: Setting<String>,
+    pub tag_prefix: Setting<String>,
+    pub python: Setting<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let layers = Layers::default();
+        // The built-in defaults always parse, so this cannot fail
+        Self::from_layers(&layers).expect("built-in configuration defaults are valid")
+    }
+}
+
+impl Config {
+    // Load the configuration for a project
+    //
+    // `file` overrides the default doc-tools.toml location, and each
+    // `overrides` entry is a KEY=VALUE pair given on the command line.
+    pub fn load(project: &Project, file: Option<&Path>, overrides: &[String]) -> Result<Self> {
+        let path = match file {
+            Some(path) => path.to_path_buf(),
+            None => project.root().join(CONFIG_FILE_NAME),
+        };
+
+        // An explicitly requested file must exist, the default one is optional
+        let table = if path.is_file() {
+            let content = fs::read_to_string(&path)
+                .map_err(|e| DocToolsError::io(format!("Failed to read {}", path.display()), e))?;
+            let table = content
+                .parse::<toml::Table>()
+                .map_err(|e| DocToolsError::Config(format!("{}: {}", path.display(), e)))?;
+            check_keys(&path, &table)?;
+            Some((path, table))
+        } else if file.is_some() {
+            return Err(DocToolsError::MissingFile(path));
+        } else {
+            None
+        };
+
+        let layers = Layers {
+            cli: parse_overrides(overrides)?,
+            env: |name| env::var(name).ok(),
+            file: table,
+        };
+
+        Self::from_layers(&layers)
+    }
+
+    fn from_layers(layers: &Layers) -> Result<Self> {
+        Ok(Self {
+            port: layers.resolve(&SERVER_PORT)?,
+            remote: layers.resolve(&DEPLOY_REMOTE)?,
+            gh_pages_branch: layers.resolve(&DEPLOY_BRANCH)?,
+            tag_prefix: layers.resolve(&TAG_PREFIX)?,
+            python: layers.resolve(&PYTHON)?,
+        })
+    }
+
+    // Every setting as (key, value, source), in a stable order
+    pub fn entries(&self) -> Vec<(&'static str, toml::Value, &Source)> {
+        let string = |setting: &Setting<String>| toml::Value::String(setting.value.clone());
+
+        vec![
+            (SERVER_PORT.name, toml::Value::Integer(self.port.value.into()), &self.port.source),
+            (DEPLOY_REMOTE.name, string(&self.remote), &self.remote.source),
+            (DEPLOY_BRANCH.name, string(&self.gh_pages_branch), &self.gh_pages_branch.source),
+            (TAG_PREFIX.name, string(&self.tag_prefix), &self.tag_prefix.source),
+            (PYTHON.name, string(&self.python), &self.python.source),
+        ]
+    }
+
+    // Git tag name for a version number, e.g. "1.2.0" -> "v1.2.0"
+    pub fn tag_name(&self, version: &str) -> String {
+        format!("{}{}", self.tag_prefix.value, version)
+    }
+}
+
+// The layers a setting can come from, highest precedence first
+struct Layers {
+    cli: Vec<(String, String)>,
+    env: fn(&str) -> Option<String>,
+    file: Option<(PathBuf, toml::Table)>,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self {
+            cli: Vec::new(),
+            env: |_| None,
+            file: None,
+        }
+    }
+}
+
+impl Layers {
+    fn resolve<T: FromStr>(&self, key: &Key) -> Result<Setting<T>> {
+        let (raw, source) = self.lookup(key)?;
+
+        let value = raw.parse().map_err(|_| {
+            DocToolsError::Config(format!("invalid value '{}' for {} (from {})", raw, key.name, source))
+        })?;
+
+        Ok(Setting { value, source })
+    }
+
+    fn lookup(&self, key: &Key) -> Result<(String, Source)> {
+        // Later command line entries win over earlier ones
+        if let Some((_, value)) = self.cli.iter().rev().find(|(name, _)| name == key.name) {
+            return Ok((value.clone(), Source::Cli));
+        }
+
+        if let Some(value) = (self.env)(key.env) {
+            return Ok((value, Source::Env(key.env)));
+        }
+
+        if let Some((path, table)) = &self.file {
+            if let Some(value) = lookup_dotted(table, key.name) {
+                let raw = match value {
+                    toml::Value::String(s) => s.clone(),
+                    toml::Value::Integer(i) => i.to_string(),
+                    other => {
+                        return Err(DocToolsError::Config(format!(
+                            "{}: {} must be a string or integer, found {}",
+                            path.display(),
+                            key.name,
+                            other.type_str()
+                        )))
+                    }
+                };
+                return Ok((raw, Source::File(path.clone())));
+            }
+        }
+
+        Ok((key.default.to_string(), Source::Default))
+    }
+}
+
+// Find `section.key` in a parsed TOML table
+fn lookup_dotted<'a>(table: &'a toml::Table, name: &str) -> Option<&'a toml::Value> {
+    let (section, key) = name.split_once('.')?;
+    table.get(section)?.as_table()?.get(key)
+}
+
+// Reject keys the tools do not know about, so a typo such as
+// `[server] prot = 9000` does not silently fall back to the default
+fn check_keys(path: &Path, table: &toml::Table) -> Result<()> {
+    for (section, value) in table {
+        let names: Vec<String> = match value.as_table() {
+            Some(keys) => keys.keys().map(|key| format!("{}.{}", section, key)).collect(),
+            None => vec![section.clone()],
+        };
+
+        if let Some(name) = names.iter().find(|name| !is_known(name)) {
+            return Err(DocToolsError::Config(format!(
+                "{}: unknown key '{}' (known keys: {})",
+                path.display(),
+                name,
+                known_keys()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_known(name: &str) -> bool {
+    KEYS.iter().any(|key| key.name == name)
+}
+
+fn known_keys() -> String {
+    KEYS.iter().map(|key| key.name).collect::<Vec<_>>().join(", ")
+}
+
+// Split KEY=VALUE overrides and reject keys the tools do not know about
+fn parse_overrides(overrides: &[String]) -> Result<Vec<(String, String)>> {
+    overrides
+        .iter()
+        .map(|entry| {
+            let (name, value) = entry
+                .split_once('=')
+                .ok_or_else(|| DocToolsError::Usage(format!("expected KEY=VALUE, got '{}'", entry)))?;
+
+            if !is_known(name) {
+                return Err(DocToolsError::Usage(format!(
+                    "unknown configuration key '{}' (known keys: {})",
+                    name,
+                    known_keys()
+                )));
+            }
+
+            Ok((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,68 @@
+use std::sync::Arc;
+
+use crate::cleanup;
+use crate::cli::GlobalArgs;
+use crate::config::Config;
+use crate::error::Result;
+use crate::exec::{CommandRunner, DryRunRunner, SystemRunner};
+use crate::log;
+use crate::output::OutputFormat;
+use crate::project::Project;
+use crate::style;
+
+// Everything a tool needs to know about where and how it runs
+#[derive(Debug, Clone)]
+pub struct Context {
+    pub project: Project,
+    pub config: Config,
+    pub runner: Arc<dyn CommandRunner>,
+    pub dry_run: bool,
+    pub output: OutputFormat,
+}
+
+impl Context {
+    pub fn new(project: Project, config: Config, runner: Arc<dyn CommandRunner>) -> Self {
+        Self {
+            project,
+            config,
+            runner,
+            dry_run: false,
+            output: OutputFormat::Text,
+        }
+    }
+
+    // Resolve the project and load its configuration from the global options
+    pub fn load(global: &GlobalArgs) -> Result<Self> {
+        let runner = Self::runtime(global)?;
+
+        let project = Project::resolve(global.project_root.as_deref())?;
+        let config = Config::load(&project, global.config_file.as_deref(), &global.set)?;
+        crate::debug!("Project root: {}", project.root().display());
+
+        Ok(Self {
+            dry_run: global.dry_run,
+            output: global.output,
+            ..Self::new(project, config, runner)
+        })
+    }
+
+    // Set up styling and logging and build the command runner, for commands
+    // such as `self rebuild` that do not operate on a documentation project
+    pub fn runtime(global: &GlobalArgs) -> Result<Arc<dyn CommandRunner>> {
+        style::init(global.color);
+        log::init(global.log_level(), global.log_file.as_deref())?;
+        log::reserve_stdout(global.output.is_json());
+        cleanup::install_signal_handler();
+
+        let mut system = SystemRunner::new();
+        if global.output.is_json() {
+            system = system.reserve_stdout();
+        }
+
+        Ok(if global.dry_run {
+            Arc::new(DryRunRunner::new(system))
+        } else {
+            Arc::new(system)
+        })
+    }
+}
@@ -0,0 +1,475 @@
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::cleanup::BranchGuard;
+use crate::config::Config;
+use crate::context::Context;
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::log::{self, Phase};
+use crate::project::Project;
+use crate::style::{BLUE, GREEN, NC, RED, YELLOW};
+use crate::venv;
+use crate::{debug, error, info, prompt, say, warn};
+
+// Prompt the user to choose between regular and force deployment
+pub fn select_deployment_mode() -> bool {
+    // Clear the terminal to make the prompt more visible
+    say!("\n\n");
+    say!("{}============================================================{}", BLUE, NC);
+    say!("{}              DOCUMENTATION DEPLOYMENT SELECTION              {}", BLUE, NC);
+    say!("{}============================================================{}", BLUE, NC);
+    say!("\nPlease select one of the following deployment modes:");
+    say!("\n{}1) Smart Deploy (Recommended){}", GREEN, NC);
+    say!("   - Only deploy versions that aren't already in gh-pages branch");
+    say!("   - Saves time and resources by skipping versions already deployed");
+    say!("\n{}2) Force Deploy{}", YELLOW, NC);
+    say!("   - Redeploy ALL versions regardless of existing state");
+    say!("   - Takes longer but ensures consistency across all versions");
+
+    // prompt! flushes so the prompt is immediately visible
+    prompt!("\n{}Please enter your choice [1/2]: {}", BLUE, NC);
+
+    // Small delay to ensure terminal has time to display the prompt
+    thread::sleep(Duration::from_millis(100));
+
+    // Read user input
+    let mut choice = String::new();
+    match io::stdin().read_line(&mut choice) {
+        Ok(_) => match choice.trim() {
+            "1" => {
+                say!("\n{}Smart deploy selected. Only missing versions will be deployed.{}\n", GREEN, NC);
+                false
+            }
+            "2" => {
+                say!("\n{}Force deploy selected. All versions will be redeployed.{}\n", YELLOW, NC);
+                true
+            }
+            _ => {
+                say!("{}Invalid choice. Defaulting to Smart Deploy mode.{}\n", RED, NC);
+                false
+            }
+        },
+        Err(_) => {
+            say!("{}Error reading input. Defaulting to Smart Deploy mode.{}\n", RED, NC);
+            false
+        }
+    }
+}
+
+// What one deployment run did, also the `--output json` result of `deploy`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeploymentReport {
+    // Whether every version was redeployed
+    pub force: bool,
+    // Branch the versions were deployed to
+    pub branch: String,
+    // Tags found on the main branch, oldest first
+    pub tags: Vec<String>,
+    // Versions deployed by this run
+    pub deployed: Vec<String>,
+    // Versions skipped because they were already deployed
+    pub skipped: Vec<String>,
+    // Versions mike failed to deploy
+    pub failed: Vec<String>,
+    // Version the `latest` alias points to, if any tags were found
+    pub latest: Option<String>,
+}
+
+impl DeploymentReport {
+    // A partial deployment is a failure, naming the versions mike could not deploy
+    pub fn check(&self) -> Result<()> {
+        if self.failed.is_empty() {
+            Ok(())
+        } else {
+            Err(DocToolsError::Mike(format!("Failed to deploy {}.", self.failed.join(", "))))
+        }
+    }
+}
+
+pub struct Deployer {
+    project: Project,
+    config: Config,
+    runner: Arc<dyn CommandRunner>,
+    force: bool,
+    current_branch: String,
+    main_tags: Vec<String>,
+    deployed_versions: Vec<String>,
+}
+
+impl Deployer {
+    pub fn new(ctx: &Context, force: bool) -> Result<Self> {
+        let current_branch = Self::get_current_branch(ctx.runner.as_ref(), ctx.project.root())?;
+        debug!("Current branch: {}", current_branch);
+
+        Ok(Self {
+            project: ctx.project.clone(),
+            config: ctx.config.clone(),
+            runner: Arc::clone(&ctx.runner),
+            force,
+            current_branch,
+            main_tags: Vec::new(),
+            deployed_versions: Vec::new(),
+        })
+    }
+
+    pub fn run(&mut self) -> Result<DeploymentReport> {
+        self.fetch_tags_and_branch()?;
+
+        // Get main branch tags
+        self.main_tags = self.get_tags_from_main()?;
+
+        // Check for previously deployed versions if not forcing
+        if !self.force {
+            self.get_deployed_versions()?;
+        }
+
+        // Deploy versions
+        let mut report = self.deploy_versions()?;
+
+        // If we have tags, set latest and push changes
+        if !self.main_tags.is_empty() {
+            self.set_latest_alias()?;
+            self.push_gh_pages()?;
+            report.latest = self.main_tags.last().cloned();
+            self.show_completion_stats(&report);
+        } else {
+            warn!("{}No tags found in main branch. Nothing to deploy.{}", RED, NC);
+        }
+        Ok(report)
+    }
+
+    // Git command run from the project root
+    fn git(&self) -> Cmd {
+        Cmd::new("git", self.project.root())
+    }
+
+    // Mike command run from the project root
+    fn mike(&self) -> Cmd {
+        Cmd::new(venv::program(&self.project, "mike"), self.project.root())
+    }
+
+    // Branch mike deploys to
+    fn branch(&self) -> &str {
+        &self.config.gh_pages_branch.value
+    }
+
+    // Remote the deployment branch is fetched from and pushed to
+    fn remote(&self) -> &str {
+        &self.config.remote.value
+    }
+
+    // Tags found on the main branch, oldest first
+    pub fn main_tags(&self) -> &[String] {
+        &self.main_tags
+    }
+
+    // Versions detected on the gh-pages branch
+    pub fn deployed_versions(&self) -> &[String] {
+        &self.deployed_versions
+    }
+
+    fn fetch_tags_and_branch(&self) -> Result<()> {
+        log::set_phase(Phase::Fetch);
+        info!("{}Fetching tags and {} branch...{}", BLUE, self.branch(), NC);
+
+        // Fetch all tags
+        let fetch_tags = self.git().args(["fetch", "--tags"]).mutating();
+
+        if !self.runner.run(&fetch_tags)?.success() {
+            error!("{}Error: Failed to fetch git tags.{}", RED, NC);
+        }
+
+        // Fetch the deployment branch
+        let refspec = format!("{0}:{0}", self.branch());
+        let fetch_branch = self.git().args(["fetch", self.remote(), &refspec]).capture().mutating();
+
+        if !self.runner.run(&fetch_branch)?.success() {
+            warn!("{}Warning: {} branch doesn't exist yet. It will be created.{}", YELLOW, self.branch(), NC);
+        }
+        Ok(())
+    }
+
+    fn get_current_branch(runner: &dyn CommandRunner, project_root: &Path) -> Result<String> {
+        let rev_parse = Cmd::new("git", project_root)
+            .args(["rev-parse", "--abbrev-ref", "HEAD"])
+            .capture();
+        let output = runner.run(&rev_parse)?;
+
+        if !output.success() {
+            return Err(DocToolsError::Git("Failed to determine the current branch.".to_string()));
+        }
+
+        Ok(output.stdout.trim().to_string())
+    }
+
+    pub fn get_tags_from_main(&self) -> Result<Vec<String>> {
+        log::set_phase(Phase::Detect);
+        info!("{}Temporarily switching to main branch to get accurate tags...{}", BLUE, NC);
+
+        // Switch to main branch; the guard switches back on any exit path
+        let guard = BranchGuard::checkout(Arc::clone(&self.runner), self.project.root(), &self.current_branch, "main")?;
+
+        // Get all tags from the main branch
+        let output = self.runner.run(&self.git().args(["tag", "--sort=v:refname"]).capture())?;
+
+        let tags: Vec<String> = output
+            .stdout
+            .split('\n')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect();
+        debug!("Tags on main: {}", tags.join(", "));
+
+        info!("{}Found {} tags in main branch.{}", GREEN, tags.len(), NC);
+
+        // Switch back to the original branch
+        info!("{}Switching back to original branch ({})...{}", BLUE, guard.original(), NC);
+        guard.restore()?;
+
+        Ok(tags)
+    }
+
+    pub fn get_deployed_versions(&mut self) -> Result<()> {
+        if self.force {
+            return Ok(());
+        }
+        log::set_phase(Phase::Detect);
+
+        // Check if the deployment branch exists
+        let verify_branch = self.git().args(["rev-parse", "--verify", self.branch()]).capture();
+
+        if !self.runner.run(&verify_branch)?.success() {
+            info!("{}No {} branch found. Will deploy all versions.{}", YELLOW, self.branch(), NC);
+            return Ok(());
+        }
+
+        // Let's try a different approach to get deployed versions
+        // Instead of parsing the JSON file, we'll check the directories directly
+        // since mike creates a directory for each version
+        info!("{}Checking for deployed versions in {} branch...{}", BLUE, self.branch(), NC);
+
+        // First, get a list of directories in the deployment branch root
+        let output = self
+            .runner
+            .run(&self.git().args(["ls-tree", "--name-only", self.branch()]).capture())?;
+
+        if !output.success() {
+            warn!("{}Failed to list contents of {} branch. Will deploy all versions.{}", YELLOW, self.branch(), NC);
+            return Ok(());
+        }
+
+        let dirs = &output.stdout;
+        debug!("{} root entries: {}", self.branch(), dirs.lines().collect::<Vec<_>>().join(", "));
+        let detected_versions = versions_in_listing(dirs, &self.config.tag_prefix.value);
+
+        // Also check if we can find versions in the versions.json file as backup
+        let json_versions = self.get_versions_from_json();
+        debug!("versions.json lists: {}", json_versions.join(", "));
+
+        // Combine both methods
+        self.deployed_versions = detected_versions;
+        for version in json_versions {
+            if !self.deployed_versions.contains(&version) {
+                self.deployed_versions.push(version);
+            }
+        }
+
+        if !self.deployed_versions.is_empty() {
+            info!("{}Found {} already deployed versions:{}", GREEN, self.deployed_versions.len(), NC);
+            for version in &self.deployed_versions {
+                info!("  {}", version);
+            }
+        } else {
+            info!("{}No previously deployed versions detected. Will deploy all versions.{}", YELLOW, NC);
+        }
+        Ok(())
+    }
+
+    // New method to get versions from versions.json as a backup strategy
+    fn get_versions_from_json(&self) -> Vec<String> {
+        Self::versions_json_on(self.runner.as_ref(), self.project.root(), self.branch())
+    }
+
+    // Versions listed in versions.json on `branch`, empty if it cannot be read
+    fn versions_json_on(runner: &dyn CommandRunner, project_root: &Path, branch: &str) -> Vec<String> {
+        let show = Cmd::new("git", project_root)
+            .args(["show", &format!("{}:versions.json", branch)])
+            .capture();
+
+        match runner.run(&show) {
+            Ok(output) if output.success() => Self::parse_versions_json(&output.stdout),
+            _ => Vec::new(),
+        }
+    }
+
+    // Versions already on the deployment branch, found without printing anything
+    //
+    // Meant for status displays: a missing branch simply yields no versions.
+    pub fn list_deployed_versions(runner: &dyn CommandRunner, project_root: &Path, config: &Config) -> Vec<String> {
+        let branch = config.gh_pages_branch.value.as_str();
+        let ls_tree = Cmd::new("git", project_root)
+            .args(["ls-tree", "--name-only", branch])
+            .capture();
+
+        let mut versions = match runner.run(&ls_tree) {
+            Ok(output) if output.success() => versions_in_listing(&output.stdout, &config.tag_prefix.value),
+            _ => Vec::new(),
+        };
+
+        for version in Self::versions_json_on(runner, project_root, branch) {
+            if !versions.contains(&version) {
+                versions.push(version);
+            }
+        }
+        versions
+    }
+
+    pub fn parse_versions_json(json_content: &str) -> Vec<String> {
+        let mut versions = Vec::new();
+
+        // Improved JSON parsing - look for any format that might contain version info
+        for line in json_content.lines() {
+            // Look for version patterns in the JSON
+            for pattern in &[r#""version":"#, r#"{"version":"#, r#""name":"#, r#""id":"#] {
+                if let Some(pos) = line.find(pattern) {
+                    let start = pos + pattern.len();
+                    if let Some(end) = line[start..].find('"') {
+                        let version = line[start..(start + end)].trim();
+                        // Only accept versions that look like v0.1.2 format
+                        if version.starts_with('v') || version.chars().next().unwrap_or('x').is_ascii_digit() {
+                            versions.push(version.to_string());
+                        }
+                    }
+                }
+            }
+        }
+
+        versions
+    }
+
+    fn deploy_versions(&self) -> Result<DeploymentReport> {
+        log::set_phase(Phase::Deploy);
+        info!("{}Deploying versions to {} branch...{}", BLUE, self.branch(), NC);
+
+        if self.force {
+            info!("{}Force mode enabled. All versions will be deployed regardless of existing state.{}", YELLOW, NC);
+        } else if self.deployed_versions.is_empty() {
+            info!("{}No previously deployed versions found.{}", YELLOW, NC);
+        }
+
+        let mut report = DeploymentReport {
+            force: self.force,
+            branch: self.branch().to_string(),
+            tags: self.main_tags.clone(),
+            ..DeploymentReport::default()
+        };
+
+        for tag in &self.main_tags {
+            // Check if this version is already deployed and we're not forcing
+            if !self.force && self.deployed_versions.contains(tag) {
+                info!("{}Skipping version {} (already deployed){}", YELLOW, tag, NC);
+                report.skipped.push(tag.clone());
+                continue;
+            }
+
+            info!("{}Deploying version: {}{}", BLUE, tag, NC);
+
+            let deploy = self.mike().args(["deploy", tag, "--branch", self.branch()]).mutating();
+
+            if self.runner.run(&deploy)?.success() {
+                report.deployed.push(tag.clone());
+            } else {
+                error!("{}Error: Failed to deploy version {}.{}", RED, tag, NC);
+                report.failed.push(tag.clone());
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub fn set_latest_alias(&self) -> Result<()> {
+        match self.main_tags.last() {
+            Some(latest_tag) => self.point_latest_at(latest_tag),
+            None => Ok(()),
+        }
+    }
+
+    // Point the `latest` alias and the default version at an already deployed tag
+    pub fn point_latest_at(&self, latest_tag: &str) -> Result<()> {
+        log::set_phase(Phase::Alias);
+
+        info!("\n{}Setting 'latest' alias to: {}{}", BLUE, latest_tag, NC);
+
+        // Set the tag as latest
+        let alias = self
+            .mike()
+            .args(["deploy", latest_tag, "latest", "--branch", self.branch(), "--update-aliases"])
+            .mutating();
+
+        if !self.runner.run(&alias)?.success() {
+            return Err(DocToolsError::Mike(format!("Failed to set {} as latest.", latest_tag)));
+        }
+
+        // Set default to latest
+        let set_default = self
+            .mike()
+            .args(["set-default", "latest", "--branch", self.branch()])
+            .mutating();
+
+        if !self.runner.run(&set_default)?.success() {
+            return Err(DocToolsError::Mike("Failed to set default version.".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn push_gh_pages(&self) -> Result<()> {
+        log::set_phase(Phase::Push);
+        info!("{}Pushing {} branch to {}...{}", BLUE, self.branch(), self.remote(), NC);
+
+        let push = self.git().args(["push", self.remote(), self.branch()]).mutating();
+
+        if !self.runner.run(&push)?.success() {
+            return Err(DocToolsError::Git(format!("Failed to push {} branch.", self.branch())));
+        }
+        Ok(())
+    }
+
+    fn show_completion_stats(&self, report: &DeploymentReport) {
+        let total_tags = self.main_tags.len();
+        let latest_tag = self.main_tags.last().map_or("none", String::as_str);
+
+        info!("\n{}Deployment complete!{}", GREEN, NC);
+        info!("{}Tags processed: {}{}", GREEN, total_tags, NC);
+        info!("{}  - Deployed: {}{}", GREEN, report.deployed.len(), NC);
+
+        if !self.force {
+            info!("{}  - Skipped (already deployed): {}{}", GREEN, report.skipped.len(), NC);
+        }
+
+        info!("{}\'latest\' is now pointing to: {}{}", GREEN, latest_tag, NC);
+        info!("\n{}Note: You can use the --force (-f) option to redeploy all versions.{}", YELLOW, NC);
+    }
+}
+
+// Entries of a deployment branch listing that look like deployed versions
+//
+// mike creates a directory per version, so these are the entries named like
+// our tags: the tag prefix followed by something with digits, e.g. v0.1.5.
+fn versions_in_listing(listing: &str, tag_prefix: &str) -> Vec<String> {
+    listing
+        .lines()
+        .map(str::trim)
+        .filter(|entry| {
+            entry
+                .strip_prefix(tag_prefix)
+                .is_some_and(|rest| rest.chars().any(|c| c.is_ascii_digit()))
+        })
+        .map(str::to_string)
+        .collect()
+}
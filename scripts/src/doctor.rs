@@ -0,0 +1,448 @@
+// Environment diagnostics for `doc-cli doctor`
+//
+// Every check only reads state, so the doctor is safe to run at any time,
+// including in a dry run. Each finding carries a hint on how to fix it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::context::Context;
+use crate::exec::{Cmd, CommandOutput, CommandRunner};
+use crate::port;
+use crate::project::Project;
+use crate::startup::Environment;
+use crate::venv::{self, PythonVersion, Venv, MIN_PYTHON};
+
+// Prints "<name> <version>" per package, with "-" for missing packages
+const PACKAGE_VERSIONS: &str = "\
+import sys
+from importlib import metadata
+for name in sys.argv[1:]:
+    try:
+        print(name, metadata.version(name))
+    except metadata.PackageNotFoundError:
+        print(name, '-')";
+
+// How a check turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    // Something only some commands need is missing or odd
+    Warn,
+    // A command will fail until this is fixed
+    Fail,
+}
+
+// Result of a single check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+    pub detail: String,
+    // How to fix the problem, None for passing checks
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            outcome: Outcome::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            outcome: Outcome::Warn,
+            hint: Some(hint.into()),
+            ..Self::pass(name, detail)
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            outcome: Outcome::Fail,
+            hint: Some(hint.into()),
+            ..Self::pass(name, detail)
+        }
+    }
+}
+
+// Every check in the order it ran, also the `--output json` result of `doctor`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DoctorReport {
+    pub checks: Vec<Check>,
+    pub passed: usize,
+    pub warnings: usize,
+    pub failures: usize,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<Check>) -> Self {
+        let count = |outcome| checks.iter().filter(|check| check.outcome == outcome).count();
+        Self {
+            passed: count(Outcome::Pass),
+            warnings: count(Outcome::Warn),
+            failures: count(Outcome::Fail),
+            checks,
+        }
+    }
+
+    // True when nothing failed; warnings are allowed
+    pub fn healthy(&self) -> bool {
+        self.failures == 0
+    }
+}
+
+// A requirements.txt entry with the minimum version it asks for, if any
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Requirement {
+    pub name: String,
+    pub minimum: Option<String>,
+}
+
+// Names and `>=`/`==` versions from requirements.txt; other specifiers only
+// require the package to be installed
+pub fn parse_requirements(content: &str) -> Vec<Requirement> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('-'))
+        .map(|line| {
+            let end = line.find(|c: char| "<>=!~;[ ".contains(c)).unwrap_or(line.len());
+            let spec = line[end..].trim();
+            let minimum = spec
+                .strip_prefix(">=")
+                .or_else(|| spec.strip_prefix("=="))
+                .map(|version| version.split([',', ';']).next().unwrap_or("").trim().to_string());
+
+            Requirement {
+                name: line[..end].to_string(),
+                minimum,
+            }
+        })
+        .collect()
+}
+
+// Compare dotted versions numerically, ignoring suffixes such as "rc1"
+pub fn version_at_least(installed: &str, minimum: &str) -> bool {
+    let numbers = |version: &str| -> Vec<u64> {
+        version
+            .split('.')
+            .map(|part| {
+                let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+                digits.parse().unwrap_or(0)
+            })
+            .collect()
+    };
+
+    let (mut installed, mut minimum) = (numbers(installed), numbers(minimum));
+    let len = installed.len().max(minimum.len());
+    installed.resize(len, 0);
+    minimum.resize(len, 0);
+    installed >= minimum
+}
+
+// Runs the checks against a project
+pub struct Doctor {
+    project: Project,
+    config: Config,
+    runner: Arc<dyn CommandRunner>,
+    // The project's .venv interpreter once startup created it, before that
+    // the one startup would create the venv with
+    python: String,
+    // Why no interpreter startup could use was found
+    no_python: Option<String>,
+}
+
+impl Doctor {
+    pub fn new(ctx: &Context) -> Self {
+        let venv = Venv::at(&ctx.project);
+        let (python, no_python) = if venv.exists() {
+            (venv.python().to_string_lossy().into_owned(), None)
+        } else {
+            match venv::find_interpreter(ctx.runner.as_ref(), ctx.project.root(), &ctx.config) {
+                Ok((program, _)) => (program, None),
+                Err(e) => (ctx.config.python.value.clone(), Some(e.to_string())),
+            }
+        };
+
+        Self {
+            project: ctx.project.clone(),
+            config: ctx.config.clone(),
+            runner: Arc::clone(&ctx.runner),
+            python,
+            no_python,
+        }
+    }
+
+    // Run every check; problems are reported in the result, never as errors
+    pub fn run(&self) -> DoctorReport {
+        let mut checks = vec![
+            self.tool("rustc", "rustc", &["--version"], "Install Rust from https://rustup.rs"),
+            self.tool("cargo", "cargo", &["--version"], "Install Rust from https://rustup.rs"),
+        ];
+
+        let python = self.python();
+        let python_ok = python.outcome == Outcome::Pass;
+        checks.push(python);
+
+        if python_ok {
+            checks.push(self.tool(
+                "pip",
+                &self.python,
+                &["-m", "pip", "--version"],
+                format!("{} -m ensurepip --upgrade", self.python).as_str(),
+            ));
+            checks.extend(self.python_packages());
+            checks.push(self.plugin_import());
+        } else {
+            let unavailable = format!("{} is not available", self.python);
+            for name in ["pip", "mkdocs", "mike", "requirements", "mkdocs_plugins"] {
+                checks.push(Check::fail(name, unavailable.clone(), "Fix the python check first"));
+            }
+        }
+
+        checks.push(self.tool("git", "git", &["--version"], "Install git from https://git-scm.com"));
+        checks.push(self.port());
+        checks.push(self.deploy_branch());
+        checks.push(self.remote());
+        checks.push(self.env_keys());
+
+        DoctorReport::new(checks)
+    }
+
+    fn cmd(&self, program: &str) -> Cmd {
+        Cmd::new(program, self.project.root())
+    }
+
+    // Captured output of a read-only command, None if it could not be started
+    fn probe(&self, cmd: Cmd) -> Option<CommandOutput> {
+        self.runner.run(&cmd.capture()).ok()
+    }
+
+    // A program that must run successfully, reporting its first output line
+    fn tool(&self, name: &str, program: &str, args: &[&str], hint: &str) -> Check {
+        match self.probe(self.cmd(program).args(args.iter().copied())) {
+            Some(output) if output.success() => {
+                let text = if output.stdout.trim().is_empty() { &output.stderr } else { &output.stdout };
+                Check::pass(name, text.lines().next().unwrap_or("").trim())
+            }
+            Some(CommandOutput { code: Some(code), .. }) => {
+                Check::fail(name, format!("'{}' exited with {}", program, code), hint)
+            }
+            Some(_) => Check::fail(name, format!("'{}' was terminated by a signal", program), hint),
+            None => Check::fail(name, format!("'{}' not found", program), hint),
+        }
+    }
+
+    // The interpreter startup and the checks below use, at the version CI uses
+    fn python(&self) -> Check {
+        let hint = "Install Python 3 or point python.executable in doc-tools.toml at it";
+        if let Some(reason) = &self.no_python {
+            return Check::fail("python", reason.as_str(), hint);
+        }
+
+        let check = self.tool("python", &self.python, &["--version"], hint);
+
+        match PythonVersion::parse(&check.detail) {
+            Some(version) if check.outcome == Outcome::Pass && !version.supported() => Check::fail(
+                "python",
+                format!("{} is older than {}.{}", check.detail, MIN_PYTHON.0, MIN_PYTHON.1),
+                hint,
+            ),
+            _ => check,
+        }
+    }
+
+    // mkdocs, mike and the rest of requirements.txt
+    fn python_packages(&self) -> Vec<Check> {
+        let install_hint = format!("{} -m pip install -r requirements.txt", self.python);
+        let requirements_path = self.project.requirements_txt();
+
+        let requirements = match fs::read_to_string(&requirements_path) {
+            Ok(content) => parse_requirements(&content),
+            Err(_) => {
+                return vec![Check::fail(
+                    "requirements",
+                    format!("{} not found", requirements_path.display()),
+                    "Run doc-cli from the documentation project or pass --project-root",
+                )]
+            }
+        };
+
+        let mut names: Vec<&str> = requirements.iter().map(|req| req.name.as_str()).collect();
+        for tool in ["mkdocs", "mike"] {
+            if !names.contains(&tool) {
+                names.push(tool);
+            }
+        }
+
+        let query = self
+            .cmd(&self.python)
+            .args(["-c", PACKAGE_VERSIONS])
+            .args(names.iter().copied());
+        let installed: BTreeMap<String, String> = match self.probe(query) {
+            Some(output) if output.success() => output
+                .stdout
+                .lines()
+                .filter_map(|line| line.split_once(' '))
+                .filter(|(_, version)| *version != "-")
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+            _ => BTreeMap::new(),
+        };
+
+        let mut checks: Vec<Check> = ["mkdocs", "mike"]
+            .iter()
+            .map(|tool| match installed.get(*tool) {
+                Some(version) => Check::pass(tool, format!("{} {}", tool, version)),
+                None => Check::fail(tool, format!("{} is not installed", tool), install_hint.clone()),
+            })
+            .collect();
+
+        let mut problems = Vec::new();
+        for requirement in &requirements {
+            match (installed.get(&requirement.name), &requirement.minimum) {
+                (None, _) => problems.push(format!("{} missing", requirement.name)),
+                (Some(version), Some(minimum)) if !version_at_least(version, minimum) => {
+                    problems.push(format!("{} {} < {}", requirement.name, version, minimum))
+                }
+                _ => {}
+            }
+        }
+
+        checks.push(if problems.is_empty() {
+            Check::pass(
+                "requirements",
+                format!("{} packages satisfy requirements.txt", requirements.len()),
+            )
+        } else {
+            Check::fail("requirements", problems.join(", "), install_hint)
+        });
+        checks
+    }
+
+    // The project's own MkDocs plugins, installed with `pip install -e .`
+    fn plugin_import(&self) -> Check {
+        let import = self.cmd(&self.python).args(["-c", "import mkdocs_plugins"]);
+        match self.probe(import) {
+            Some(output) if output.success() => Check::pass("mkdocs_plugins", "module imports"),
+            Some(output) => Check::fail(
+                "mkdocs_plugins",
+                output.stderr.lines().last().unwrap_or("import failed").trim(),
+                format!("{} -m pip install -e .", self.python),
+            ),
+            None => Check::fail("mkdocs_plugins", "import failed", "Fix the python check first"),
+        }
+    }
+
+    // Only startup needs the port; it can kill the holder or pick another
+    fn port(&self) -> Check {
+        let port = self.config.port.value;
+        let bind = Environment::detect().default_bind();
+        if port::is_free(bind, port) {
+            return Check::pass("port", format!("{} is free", port));
+        }
+
+        let holders: Vec<String> = port::listeners(port).iter().map(|listener| listener.to_string()).collect();
+        let detail = if holders.is_empty() {
+            format!("{} is in use", port)
+        } else {
+            format!("{} is in use by {}", port, holders.join(", "))
+        };
+        Check::warn("port", detail, "Stop that process or run 'doc-cli startup --port auto'")
+    }
+
+    fn deploy_branch(&self) -> Check {
+        let branch = &self.config.gh_pages_branch.value;
+        let remote = &self.config.remote.value;
+        let exists = |reference: String| {
+            self.probe(self.cmd("git").args(["rev-parse", "--verify", "--quiet"]).arg(reference))
+                .is_some_and(|output| output.success())
+        };
+
+        if exists(format!("refs/heads/{}", branch)) {
+            Check::pass("deploy branch", format!("{} exists locally", branch))
+        } else if exists(format!("refs/remotes/{}/{}", remote, branch)) {
+            Check::pass("deploy branch", format!("{}/{} exists", remote, branch))
+        } else {
+            Check::warn(
+                "deploy branch",
+                format!("no {} branch locally or on {}", branch, remote),
+                format!("Run 'git fetch {} {}', or 'doc-cli deploy' to create it", remote, branch),
+            )
+        }
+    }
+
+    fn remote(&self) -> Check {
+        let remote = &self.config.remote.value;
+        match self.probe(self.cmd("git").args(["remote", "get-url"]).arg(remote.as_str())) {
+            Some(output) if output.success() => Check::pass("remote", format!("{} is {}", remote, output.stdout.trim())),
+            _ => Check::fail(
+                "remote",
+                format!("no '{}' remote", remote),
+                format!("git remote add {} <url>", remote),
+            ),
+        }
+    }
+
+    // Keys listed in .env.example must be set in .env, and not to the example value
+    fn env_keys(&self) -> Check {
+        let Ok(example) = fs::read_to_string(self.project.env_example()) else {
+            return Check::pass(".env", "no .env.example to compare with");
+        };
+        let Ok(actual) = fs::read_to_string(self.project.env_file()) else {
+            return Check::warn(".env", ".env does not exist", "cp .env.example .env and fill in the values");
+        };
+
+        let example = env_entries(&example);
+        let actual = env_entries(&actual);
+
+        let missing: Vec<&str> = example
+            .keys()
+            .filter(|key| !actual.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        // .env.example marks values to fill in as your_..._here
+        let placeholders: Vec<&str> = example
+            .iter()
+            .filter(|(key, value)| !value.is_empty() && actual.get(*key) == Some(value) && value.contains("your_"))
+            .map(|(key, _)| key.as_str())
+            .collect();
+
+        if !missing.is_empty() {
+            Check::warn(
+                ".env",
+                format!("missing {}", missing.join(", ")),
+                "Copy the missing keys from .env.example into .env",
+            )
+        } else if !placeholders.is_empty() {
+            Check::warn(
+                ".env",
+                format!("{} still set to the example value", placeholders.join(", ")),
+                "Replace the placeholder values in .env",
+            )
+        } else {
+            Check::pass(".env", format!("all {} keys from .env.example are set", example.len()))
+        }
+    }
+}
+
+// KEY=VALUE lines of a dotenv file, ignoring comments and `export`
+fn env_entries(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.strip_prefix("export ").unwrap_or(line).split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().trim_matches(['"', '\'']).to_string()))
+        .collect()
+}
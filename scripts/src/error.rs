@@ -0,0 +1,118 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::workflow::StepResult;
+
+// Result type used by every documentation tool operation
+pub type Result<T> = std::result::Result<T, DocToolsError>;
+
+// Errors that can occur while running the documentation tools
+#[derive(Debug)]
+pub enum DocToolsError {
+    // A git command could not be run or exited unsuccessfully
+    Git(String),
+    // A mike command could not be run or exited unsuccessfully
+    Mike(String),
+    // A python, pip or mkdocs command could not be run or exited unsuccessfully
+    Python(String),
+    // cargo could not be run or failed to build the tools
+    Build(String),
+    // A doc-cli-<name> plugin exited unsuccessfully; doc-cli exits with its code
+    Plugin { name: String, code: i32 },
+    // A file the tool depends on does not exist
+    MissingFile(PathBuf),
+    // No documentation project could be found from the given directory
+    ProjectNotFound(PathBuf),
+    // The user declined to continue
+    UserAbort(String),
+    // The command line arguments were not understood
+    Usage(String),
+    // doc-tools.toml or a configuration override is invalid
+    Config(String),
+    // A `doc-cli run` workflow file is invalid or refers to an unknown variable
+    Workflow(String),
+    // Another process listens on the server port and was left running
+    PortInUse { port: u16, reason: String },
+    // A workflow step failed; doc-cli exits with the step's own exit code.
+    // `steps` is how every step of the run ended, for the JSON error document
+    Step { name: String, source: Box<DocToolsError>, steps: Vec<StepResult> },
+    // Any other I/O failure, with a description of what was being attempted
+    Io { context: String, source: io::Error },
+}
+
+impl DocToolsError {
+    // Wrap an I/O error with a description of what was being attempted
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Self::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    // Process exit code a binary should use when it fails with this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::UserAbort(_) => 130,
+            Self::Usage(_) => 2,
+            Self::Plugin { code, .. } => *code,
+            Self::Step { source, .. } => source.exit_code(),
+            _ => 1,
+        }
+    }
+
+    // Stable machine-readable name of the error variant
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Git(_) => "git",
+            Self::Mike(_) => "mike",
+            Self::Python(_) => "python",
+            Self::Build(_) => "build",
+            Self::Plugin { .. } => "plugin",
+            Self::MissingFile(_) => "missing_file",
+            Self::ProjectNotFound(_) => "project_not_found",
+            Self::UserAbort(_) => "user_abort",
+            Self::Usage(_) => "usage",
+            Self::Config(_) => "config",
+            Self::Workflow(_) => "workflow",
+            Self::PortInUse { .. } => "port_in_use",
+            Self::Step { .. } => "step",
+            Self::Io { .. } => "io",
+        }
+    }
+}
+
+impl fmt::Display for DocToolsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Git(message) => write!(f, "git: {}", message),
+            Self::Mike(message) => write!(f, "mike: {}", message),
+            Self::Python(message) => write!(f, "python: {}", message),
+            Self::Build(message) => write!(f, "cargo: {}", message),
+            Self::Plugin { name, code } => write!(f, "plugin doc-cli-{} exited with status {}", name, code),
+            Self::MissingFile(path) => write!(f, "required file not found: {}", path.display()),
+            Self::ProjectNotFound(path) => write!(
+                f,
+                "no mkdocs.yml or git repository found at or above {} (use --project-root to set it)",
+                path.display()
+            ),
+            Self::UserAbort(message) => write!(f, "{}", message),
+            Self::Usage(message) => write!(f, "{}", message),
+            Self::Config(message) => write!(f, "configuration: {}", message),
+            Self::Workflow(message) => write!(f, "workflow: {}", message),
+            Self::PortInUse { port, reason } => write!(f, "port {} is in use: {}", port, reason),
+            Self::Step { name, source, .. } => write!(f, "step '{}' failed: {}", name, source),
+            Self::Io { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for DocToolsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Step { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
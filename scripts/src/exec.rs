@@ -0,0 +1,444 @@
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use crate::error::{DocToolsError, Result};
+
+// Whether running a command changes anything outside the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    // Only reads state, so it also runs during a dry run
+    ReadOnly,
+    // Changes the repository, the environment or other processes
+    Mutating,
+}
+
+// How the command's standard streams are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stdout {
+    // Shown to the user as the command runs
+    Inherit,
+    // Captured into CommandOutput
+    Capture,
+    // Discarded
+    Null,
+}
+
+// A single external command invocation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cmd {
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: PathBuf,
+    // Extra environment variables on top of the inherited environment
+    pub env: Vec<(String, String)>,
+    pub effect: Effect,
+    pub stdout: Stdout,
+    // File that stdout and stderr are appended to instead of being shown
+    pub log: Option<PathBuf>,
+}
+
+impl Cmd {
+    // A read-only command whose output is shown to the user
+    pub fn new(program: impl Into<String>, cwd: &Path) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+            cwd: cwd.to_path_buf(),
+            env: Vec::new(),
+            effect: Effect::ReadOnly,
+            stdout: Stdout::Inherit,
+            log: None,
+        }
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+
+    // Mark the command as changing state, so a dry run skips it
+    pub fn mutating(mut self) -> Self {
+        self.effect = Effect::Mutating;
+        self
+    }
+
+    // Capture stdout and stderr instead of showing them
+    pub fn capture(mut self) -> Self {
+        self.stdout = Stdout::Capture;
+        self
+    }
+
+    // Discard stdout and stderr
+    pub fn quiet(mut self) -> Self {
+        self.stdout = Stdout::Null;
+        self
+    }
+
+    // Append stdout and stderr to `path`, e.g. a background server's log
+    pub fn log_to(mut self, path: impl Into<PathBuf>) -> Self {
+        self.log = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for Cmd {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.program)?;
+        for arg in &self.args {
+            if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '\'' || c == '"') {
+                write!(f, " '{}'", arg.replace('\'', r"'\''"))?;
+            } else {
+                write!(f, " {}", arg)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Exit code and captured output of a finished command
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    // None when the process was terminated by a signal
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    // Successful output with the given stdout
+    pub fn ok(stdout: impl Into<String>) -> Self {
+        Self {
+            code: Some(0),
+            stdout: stdout.into(),
+            stderr: String::new(),
+        }
+    }
+
+    // Unsuccessful output with the given exit code
+    pub fn failed(code: i32) -> Self {
+        Self {
+            code: Some(code),
+            ..Self::default()
+        }
+    }
+
+    // Output reported for a command that was skipped by a dry run
+    pub fn skipped() -> Self {
+        Self::ok("")
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+// A command started in the background, such as the documentation server
+pub trait Process: fmt::Debug + Send {
+    // Operating system process id, None if nothing was actually started
+    fn id(&self) -> Option<u32>;
+
+    // Wait for the process to exit
+    fn wait(&mut self) -> Result<CommandOutput>;
+
+    // The exit status if the process has exited, without blocking
+    fn try_wait(&mut self) -> Result<Option<CommandOutput>>;
+}
+
+// Everything the tools run outside the process goes through a CommandRunner
+pub trait CommandRunner: fmt::Debug + Send + Sync {
+    // Run a command to completion
+    //
+    // A command that starts but exits unsuccessfully is not an error here;
+    // callers inspect `CommandOutput::success` and decide what it means.
+    fn run(&self, cmd: &Cmd) -> Result<CommandOutput>;
+
+    // Start a command without waiting for it; captured output is not supported
+    fn spawn(&self, cmd: &Cmd) -> Result<Box<dyn Process>>;
+}
+
+impl<R: CommandRunner + ?Sized> CommandRunner for Arc<R> {
+    fn run(&self, cmd: &Cmd) -> Result<CommandOutput> {
+        (**self).run(cmd)
+    }
+
+    fn spawn(&self, cmd: &Cmd) -> Result<Box<dyn Process>> {
+        (**self).spawn(cmd)
+    }
+}
+
+// Runs commands with std::process
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRunner {
+    reserve_stdout: bool,
+}
+
+impl SystemRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Send inherited command output to stderr so stdout only carries results
+    pub fn reserve_stdout(mut self) -> Self {
+        self.reserve_stdout = true;
+        self
+    }
+
+    // std::process::Command for `cmd` with its streams connected
+    fn command(&self, cmd: &Cmd) -> Result<Command> {
+        crate::trace!("run {}  (in {})", cmd, cmd.cwd.display());
+
+        let mut command = Command::new(&cmd.program);
+        command.args(&cmd.args).current_dir(&cmd.cwd).envs(cmd.env.iter().cloned());
+
+        match cmd.stdout {
+            Stdout::Inherit if self.reserve_stdout => {
+                command.stdout(Stdio::from(io::stderr()));
+            }
+            Stdout::Inherit | Stdout::Capture => {}
+            Stdout::Null => {
+                command.stdout(Stdio::null()).stderr(Stdio::null());
+            }
+        }
+
+        if let Some(path) = &cmd.log {
+            let open_failed = |e| DocToolsError::io(format!("Failed to open {}", path.display()), e);
+            let file = OpenOptions::new().create(true).append(true).open(path).map_err(open_failed)?;
+            let stderr = file.try_clone().map_err(open_failed)?;
+            command.stdout(file).stderr(stderr);
+        }
+        Ok(command)
+    }
+}
+
+fn failed_to_run(cmd: &Cmd) -> impl FnOnce(io::Error) -> DocToolsError + '_ {
+    move |e| DocToolsError::io(format!("Failed to run {}", cmd.program), e)
+}
+
+fn trace_exit(program: &str, output: &CommandOutput) {
+    match output.code {
+        Some(code) => crate::trace!("{} exited with {}", program, code),
+        None => crate::trace!("{} was terminated by a signal", program),
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, cmd: &Cmd) -> Result<CommandOutput> {
+        let mut command = self.command(cmd)?;
+
+        let output = if cmd.stdout == Stdout::Capture {
+            let output = command.output().map_err(failed_to_run(cmd))?;
+            CommandOutput {
+                code: output.status.code(),
+                stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }
+        } else {
+            let status = command.status().map_err(failed_to_run(cmd))?;
+            CommandOutput {
+                code: status.code(),
+                ..CommandOutput::default()
+            }
+        };
+
+        trace_exit(&cmd.program, &output);
+        Ok(output)
+    }
+
+    fn spawn(&self, cmd: &Cmd) -> Result<Box<dyn Process>> {
+        let child = self.command(cmd)?.spawn().map_err(failed_to_run(cmd))?;
+
+        crate::trace!("{} started with pid {}", cmd.program, child.id());
+        Ok(Box::new(ChildProcess {
+            program: cmd.program.clone(),
+            child,
+        }))
+    }
+}
+
+// A process started by SystemRunner::spawn
+#[derive(Debug)]
+struct ChildProcess {
+    program: String,
+    child: Child,
+}
+
+impl Process for ChildProcess {
+    fn id(&self) -> Option<u32> {
+        Some(self.child.id())
+    }
+
+    fn wait(&mut self) -> Result<CommandOutput> {
+        let status = self
+            .child
+            .wait()
+            .map_err(|e| DocToolsError::io(format!("Failed to wait for {}", self.program), e))?;
+        let output = CommandOutput {
+            code: status.code(),
+            ..CommandOutput::default()
+        };
+
+        trace_exit(&self.program, &output);
+        Ok(output)
+    }
+
+    fn try_wait(&mut self) -> Result<Option<CommandOutput>> {
+        let status = self
+            .child
+            .try_wait()
+            .map_err(|e| DocToolsError::io(format!("Failed to check on {}", self.program), e))?;
+
+        Ok(status.map(|status| {
+            let output = CommandOutput {
+                code: status.code(),
+                ..CommandOutput::default()
+            };
+            trace_exit(&self.program, &output);
+            output
+        }))
+    }
+}
+
+// A process that was never started, reported as already finished
+#[derive(Debug, Clone)]
+pub struct FinishedProcess {
+    pid: Option<u32>,
+    output: CommandOutput,
+}
+
+impl FinishedProcess {
+    pub fn new(pid: Option<u32>, output: CommandOutput) -> Self {
+        Self { pid, output }
+    }
+}
+
+impl Process for FinishedProcess {
+    fn id(&self) -> Option<u32> {
+        self.pid
+    }
+
+    fn wait(&mut self) -> Result<CommandOutput> {
+        Ok(self.output.clone())
+    }
+
+    fn try_wait(&mut self) -> Result<Option<CommandOutput>> {
+        Ok(Some(self.output.clone()))
+    }
+}
+
+// Reports every command and only forwards the read-only ones
+#[derive(Debug, Clone, Default)]
+pub struct DryRunRunner<R> {
+    inner: R,
+}
+
+impl<R: CommandRunner> DryRunRunner<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R: CommandRunner> CommandRunner for DryRunRunner<R> {
+    fn run(&self, cmd: &Cmd) -> Result<CommandOutput> {
+        let action = match cmd.effect {
+            Effect::ReadOnly => "run ",
+            Effect::Mutating => "skip",
+        };
+        crate::say!("[dry-run] {} {}  (in {})", action, cmd, cmd.cwd.display());
+
+        match cmd.effect {
+            Effect::ReadOnly => self.inner.run(cmd),
+            Effect::Mutating => Ok(CommandOutput::skipped()),
+        }
+    }
+
+    fn spawn(&self, cmd: &Cmd) -> Result<Box<dyn Process>> {
+        match cmd.effect {
+            Effect::ReadOnly => {
+                crate::say!("[dry-run] run  {}  (in {})", cmd, cmd.cwd.display());
+                self.inner.spawn(cmd)
+            }
+            Effect::Mutating => {
+                crate::say!("[dry-run] skip {}  (in {})", cmd, cmd.cwd.display());
+                Ok(Box::new(FinishedProcess::new(None, CommandOutput::skipped())))
+            }
+        }
+    }
+}
+
+// Scripted runner for tests: returns canned output and records every call
+//
+// Responses are matched against the start of the command line as shown by
+// `Cmd`'s Display impl, e.g. "git ls-tree". The most recently added match
+// wins; commands without one succeed with empty output. Spawned commands
+// get a made-up pid and finish immediately with their scripted output.
+#[derive(Debug, Default)]
+pub struct FakeRunner {
+    responses: Vec<(String, CommandOutput)>,
+    calls: Mutex<Vec<Cmd>>,
+}
+
+impl FakeRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Answer commands starting with `prefix` with `output`
+    pub fn on(mut self, prefix: impl Into<String>, output: CommandOutput) -> Self {
+        self.responses.push((prefix.into(), output));
+        self
+    }
+
+    // Every command received so far, in order
+    pub fn calls(&self) -> Vec<Cmd> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    // Command lines received so far, in order
+    pub fn command_lines(&self) -> Vec<String> {
+        self.calls().iter().map(ToString::to_string).collect()
+    }
+}
+
+impl CommandRunner for FakeRunner {
+    fn run(&self, cmd: &Cmd) -> Result<CommandOutput> {
+        self.calls.lock().unwrap_or_else(|e| e.into_inner()).push(cmd.clone());
+
+        let line = cmd.to_string();
+        let output = self
+            .responses
+            .iter()
+            .rev()
+            .find(|(prefix, _)| line.starts_with(prefix.as_str()))
+            .map(|(_, output)| output.clone())
+            .unwrap_or_else(|| CommandOutput::ok(""));
+
+        Ok(output)
+    }
+
+    fn spawn(&self, cmd: &Cmd) -> Result<Box<dyn Process>> {
+        let output = self.run(cmd)?;
+        let pid = FAKE_PID_BASE + self.calls().len() as u32;
+        Ok(Box::new(FinishedProcess::new(Some(pid), output)))
+    }
+}
+
+// Fake pids start here so they are easy to tell apart in test output
+const FAKE_PID_BASE: u32 = 40_000;
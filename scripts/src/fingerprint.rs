@@ -0,0 +1,43 @@
+// A stable hash for fingerprints written to disk
+//
+// std's DefaultHasher may change between Rust releases, which would make
+// every recorded fingerprint look stale after a toolchain update. 64-bit
+// FNV-1a is fixed by its definition and plenty for change detection.
+
+use std::hash::Hasher;
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(OFFSET_BASIS)
+    }
+}
+
+impl Fnv1a {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The hash as 16 hex digits, the format fingerprint files store
+    pub fn hex(&self) -> String {
+        format!("{:016x}", self.finish())
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
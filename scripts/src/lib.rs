@@ -0,0 +1,38 @@
+// Documentation maintenance tools for my-life-as-a-dev
+//
+// The binaries in src/bin are thin wrappers around the types exported here,
+// so the same operations can be embedded in other tooling.
+
+pub mod bump_version;
+pub mod cleanup;
+pub mod cli;
+pub mod commands;
+pub mod config;
+pub mod context;
+pub mod deploy;
+pub mod doctor;
+pub mod error;
+pub mod exec;
+pub mod fingerprint;
+pub mod log;
+pub mod output;
+pub mod plugins;
+pub mod port;
+pub mod project;
+pub mod rebuild;
+pub mod startup;
+pub mod static_server;
+pub mod status;
+pub mod style;
+pub mod supervisor;
+pub mod tui;
+pub mod venv;
+pub mod workflow;
+
+pub use bump_version::VersionBumper;
+pub use config::Config;
+pub use context::Context;
+pub use deploy::Deployer;
+pub use error::{DocToolsError, Result};
+pub use project::Project;
+pub use startup::Startup;
@@ -0,0 +1,298 @@
+// Leveled logging shared by every tool
+//
+// Records go to the terminal according to -q/-v/-vv and, when --log-file is
+// given, are also appended to that file with a timestamp. Each record is
+// tagged with the running subcommand and the phase it belongs to, e.g.
+// `deploy/fetch`. Info records are the tools' normal status output, so they
+// are printed unchanged; debug and trace records carry a label instead.
+//
+// Menus and prompts are not log records: say! and prompt! print them
+// regardless of the level, on stderr when stdout is reserved for JSON.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::{DocToolsError, Result};
+use crate::style::{self, Stream};
+
+// How important a record is; each level includes the ones above it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    // Terminal level selected by -q and the number of -v flags
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Warn,
+            (false, 0) => Self::Info,
+            (false, 1) => Self::Debug,
+            (false, _) => Self::Trace,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+}
+
+// Step of a subcommand a record belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    // Fetching tags and the deployment branch
+    Fetch,
+    // Working out which versions exist and which are deployed
+    Detect,
+    // Running `mike deploy` for versions
+    Deploy,
+    // Pointing the `latest` alias at a version
+    Alias,
+    // Pushing tags or the deployment branch
+    Push,
+    // Creating a version tag
+    Tag,
+    // Installing Python dependencies
+    Install,
+    // Checking the server port
+    Port,
+    // Building and serving the site
+    Serve,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Fetch => "fetch",
+            Self::Detect => "detect",
+            Self::Deploy => "deploy",
+            Self::Alias => "alias",
+            Self::Push => "push",
+            Self::Tag => "tag",
+            Self::Install => "install",
+            Self::Port => "port",
+            Self::Serve => "serve",
+        };
+        f.write_str(name)
+    }
+}
+
+struct Logger {
+    level: Level,
+    file: Option<File>,
+    command: Option<&'static str>,
+    phase: Option<Phase>,
+    stdout_reserved: bool,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+    level: Level::Info,
+    file: None,
+    command: None,
+    phase: None,
+    stdout_reserved: false,
+});
+
+fn logger() -> std::sync::MutexGuard<'static, Logger> {
+    LOGGER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// Set the terminal level and the optional file records are appended to
+pub fn init(level: Level, log_file: Option<&Path>) -> Result<()> {
+    let file = match log_file {
+        Some(path) => Some(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| DocToolsError::io(format!("Failed to open log file {}", path.display()), e))?,
+        ),
+        None => None,
+    };
+
+    let mut logger = logger();
+    logger.level = level;
+    logger.file = file;
+    Ok(())
+}
+
+// Tag following records with a subcommand, clearing the current phase
+pub fn set_command(name: &'static str) {
+    let mut logger = logger();
+    logger.command = Some(name);
+    logger.phase = None;
+}
+
+// Tag following records with a phase of the current subcommand
+pub fn set_phase(phase: Phase) {
+    logger().phase = Some(phase);
+}
+
+// Send everything meant for people to stderr, leaving stdout to results
+pub fn reserve_stdout(reserved: bool) {
+    logger().stdout_reserved = reserved;
+}
+
+// Whether stdout only carries machine-readable results
+pub fn stdout_reserved() -> bool {
+    logger().stdout_reserved
+}
+
+// Whether records at `level` are printed to the terminal
+pub fn enabled(level: Level) -> bool {
+    level <= logger().level
+}
+
+// Write one record; use the error!/warn!/info!/debug!/trace! macros instead
+#[doc(hidden)]
+pub fn write(level: Level, args: fmt::Arguments<'_>) {
+    let mut logger = logger();
+    let message = args.to_string();
+    let scope = scope(logger.command, logger.phase);
+
+    if level <= logger.level {
+        let line = match (level, &scope) {
+            (Level::Error | Level::Warn | Level::Info, _) => message.clone(),
+            (_, Some(scope)) => format!("{}({}): {}", level.label().to_lowercase(), scope, message),
+            (_, None) => format!("{}: {}", level.label().to_lowercase(), message),
+        };
+
+        // A closed pipe (e.g. `| head`) must not abort the command
+        if level == Level::Info && !logger.stdout_reserved {
+            let _ = writeln!(io::stdout().lock(), "{}", style::render(&line, Stream::Stdout));
+        } else {
+            let _ = writeln!(io::stderr().lock(), "{}", style::render(&line, Stream::Stderr));
+        }
+    }
+
+    // The log file always gets debug records, and trace ones with -vv
+    let file_level = logger.level.max(Level::Debug);
+    if let (Some(file), true) = (logger.file.as_mut(), level <= file_level) {
+        let line = format!(
+            "{} {:<5} {} {}\n",
+            timestamp(),
+            level.label(),
+            scope.as_deref().unwrap_or("-"),
+            style::strip_ansi(message.trim())
+        );
+        // Logging must never make a command fail
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+// Print a menu line or prompt; use the say!/prompt! macros instead
+#[doc(hidden)]
+pub fn console(args: fmt::Arguments<'_>, newline: bool) {
+    let reserved = logger().stdout_reserved;
+    let end = if newline { "\n" } else { "" };
+    let text = args.to_string();
+
+    // A prompt must be visible before reading the answer
+    if reserved {
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "{}{}", style::render(&text, Stream::Stderr), end).and_then(|_| stderr.flush());
+    } else {
+        let mut stdout = io::stdout().lock();
+        let _ = write!(stdout, "{}{}", style::render(&text, Stream::Stdout), end).and_then(|_| stdout.flush());
+    }
+}
+
+// "deploy/fetch", "deploy" or nothing, depending on what is known
+fn scope(command: Option<&str>, phase: Option<Phase>) -> Option<String> {
+    match (command, phase) {
+        (Some(command), Some(phase)) => Some(format!("{}/{}", command, phase)),
+        (Some(command), None) => Some(command.to_string()),
+        (None, Some(phase)) => Some(phase.to_string()),
+        (None, None) => None,
+    }
+}
+
+// Current UTC time as e.g. 2024-05-01T12:34:56.789Z
+fn timestamp() -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = now.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        now.subsec_millis()
+    )
+}
+
+// Convert days since 1970-01-01 to a (year, month, day) date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Log an error, shown even with -q
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Error, format_args!($($arg)*)) };
+}
+
+// Log a warning, shown even with -q
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Warn, format_args!($($arg)*)) };
+}
+
+// Log normal status output, hidden by -q
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Info, format_args!($($arg)*)) };
+}
+
+// Log diagnostic detail, shown with -v
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Debug, format_args!($($arg)*)) };
+}
+
+// Log every external command and its result, shown with -vv
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::write($crate::log::Level::Trace, format_args!($($arg)*)) };
+}
+
+// Print a line of a menu or other interactive output
+#[macro_export]
+macro_rules! say {
+    () => { $crate::log::console(format_args!(""), true) };
+    ($($arg:tt)*) => { $crate::log::console(format_args!($($arg)*), true) };
+}
+
+// Print a prompt without a newline and flush it
+#[macro_export]
+macro_rules! prompt {
+    ($($arg:tt)*) => { $crate::log::console(format_args!($($arg)*), false) };
+}
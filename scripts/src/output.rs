@@ -0,0 +1,72 @@
+// Machine-readable results for `--output json`
+//
+// Each subcommand writes one JSON document per line to stdout:
+//
+//   {"command":"deploy","ok":true,"result":{...}}
+//   {"command":"deploy","ok":false,"error":{"kind":"git","message":"...","exit_code":1}}
+//
+// In JSON mode everything meant for people (status messages, prompts and
+// the output of git, mike and mkdocs) goes to stderr instead.
+
+use std::io::{self, Write};
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::error::{DocToolsError, Result};
+
+// How results are reported on stdout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    // Colored status messages for people
+    #[default]
+    Text,
+    // One JSON result document per command
+    Json,
+}
+
+impl OutputFormat {
+    pub fn is_json(self) -> bool {
+        self == Self::Json
+    }
+}
+
+// Write the result document of a successful command; nothing in text mode
+pub fn emit<T: Serialize>(format: OutputFormat, command: &str, result: &T) -> Result<()> {
+    if !format.is_json() {
+        return Ok(());
+    }
+
+    let result = serde_json::to_value(result)
+        .map_err(|e| DocToolsError::io("Failed to serialize result", io::Error::other(e)))?;
+    write_line(&json!({ "command": command, "ok": true, "result": result }))
+}
+
+// Write the error document of a failed command
+pub fn emit_error(command: &str, error: &DocToolsError) {
+    let mut document = json!({
+        "command": command,
+        "ok": false,
+        "error": {
+            "kind": error.kind(),
+            "message": error.to_string(),
+            "exit_code": error.exit_code(),
+        },
+    });
+
+    // A failed workflow still tells which steps ran before it stopped
+    if let DocToolsError::Step { steps, .. } = error {
+        document["error"]["steps"] = serde_json::to_value(steps).unwrap_or_default();
+    }
+
+    // The exit code still reports the failure if stdout is gone
+    let _ = write_line(&document);
+}
+
+fn write_line(document: &serde_json::Value) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", document)
+        .and_then(|_| stdout.flush())
+        .map_err(|e| DocToolsError::io("Failed to write JSON output", e))
+}
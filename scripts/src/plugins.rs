@@ -0,0 +1,165 @@
+// External doc-cli subcommands
+//
+// Any executable named `doc-cli-<name>` in the project's scripts/target/release
+// or on PATH can be run as `doc-cli <name>`, the way cargo and git find their
+// subcommands. Built-in commands always win over a plugin of the same name.
+// A plugin gets the arguments after its name and finds the project doc-cli
+// resolved in DOC_CLI_PROJECT_ROOT.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+use crate::context::Context;
+use crate::error::{DocToolsError, Result};
+use crate::exec::Cmd;
+
+// File name prefix that marks an executable as a doc-cli plugin
+pub const PREFIX: &str = "doc-cli-";
+// Environment variable holding the resolved project root
+pub const PROJECT_ROOT_ENV: &str = "DOC_CLI_PROJECT_ROOT";
+
+// An executable providing `doc-cli <name>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plugin {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Plugin {
+    // Run the plugin in the current directory and pass on its exit code
+    pub fn run(&self, ctx: &Context, args: &[String]) -> Result<()> {
+        let cwd = env::current_dir().unwrap_or_else(|_| ctx.project.root().to_path_buf());
+        let cmd = Cmd::new(self.path.to_string_lossy(), &cwd)
+            .args(args)
+            .env(PROJECT_ROOT_ENV, ctx.project.root().to_string_lossy())
+            .mutating();
+
+        let output = ctx.runner.run(&cmd)?;
+        if output.success() {
+            Ok(())
+        } else {
+            Err(DocToolsError::Plugin {
+                name: self.name.clone(),
+                // Terminated by a signal
+                code: output.code.unwrap_or(1),
+            })
+        }
+    }
+}
+
+// Directories searched for plugins, in order of precedence
+pub fn search_path(project_root: Option<&Path>) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = project_root
+        .map(|root| root.join("scripts").join("target").join("release"))
+        .into_iter()
+        .collect();
+
+    if let Some(path) = env::var_os("PATH") {
+        dirs.extend(env::split_paths(&path));
+    }
+    dirs
+}
+
+// Every plugin found, sorted by name; the first directory providing a name wins
+pub fn discover(project_root: Option<&Path>) -> Vec<Plugin> {
+    let builtins = builtin_names();
+    let mut plugins: Vec<Plugin> = Vec::new();
+
+    for dir in search_path(project_root) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for plugin in entries.filter_map(|entry| plugin_at(&entry.ok()?.path())) {
+            if !builtins.contains(&plugin.name) && !plugins.iter().any(|found| found.name == plugin.name) {
+                plugins.push(plugin);
+            }
+        }
+    }
+
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+// Usage error for a subcommand that is neither built in nor a plugin
+pub fn unknown_command(name: &str, plugins: &[Plugin]) -> DocToolsError {
+    let candidates = builtin_names()
+        .into_iter()
+        .chain(plugins.iter().map(|plugin| plugin.name.clone()));
+
+    let suggestion = candidates
+        .map(|candidate| (edit_distance(name, &candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min();
+
+    match suggestion {
+        Some((_, candidate)) => DocToolsError::Usage(format!(
+            "unrecognized subcommand '{}'; did you mean '{}'?",
+            name, candidate
+        )),
+        None => DocToolsError::Usage(format!(
+            "unrecognized subcommand '{}' (no {}{} plugin found on PATH or in scripts/target/release)",
+            name, PREFIX, name
+        )),
+    }
+}
+
+// Names and aliases of the built-in subcommands
+fn builtin_names() -> Vec<String> {
+    let command = Cli::command();
+    let mut names = vec!["help".to_string()];
+
+    for subcommand in command.get_subcommands() {
+        names.push(subcommand.get_name().to_string());
+        names.extend(subcommand.get_all_aliases().map(str::to_string));
+    }
+    names
+}
+
+fn plugin_at(path: &Path) -> Option<Plugin> {
+    let file_name = path.file_name()?.to_str()?;
+    let name = file_name.strip_prefix(PREFIX)?;
+    let name = name.strip_suffix(env::consts::EXE_SUFFIX).unwrap_or(name);
+
+    // Skips cargo's .d dependency files and other non-programs
+    if name.is_empty() || name.contains('.') || !is_executable(path) {
+        return None;
+    }
+
+    Some(Plugin {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+    })
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+// Levenshtein distance, used for "did you mean" hints
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
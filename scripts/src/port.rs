@@ -0,0 +1,269 @@
+// Finding out whether the server port is free, and who holds it if not
+//
+// Freedom is checked by binding the socket ourselves, so no external tool is
+// needed. The owning process is looked up in /proc: /proc/net/tcp{,6} maps
+// the listening socket to an inode and /proc/<pid>/fd links back to it.
+
+use std::fmt;
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+use std::str::FromStr;
+
+use clap::CommandFactory;
+
+use crate::cli::Cli;
+use crate::error::{DocToolsError, Result};
+
+// How many ports `--port auto` tries before giving up
+pub const AUTO_RANGE: u16 = 100;
+
+// The port asked for on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortSpec {
+    Fixed(u16),
+    // The first free port from server.port upwards
+    Auto,
+}
+
+impl FromStr for PortSpec {
+    type Err = String;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        if value.eq_ignore_ascii_case("auto") {
+            return Ok(Self::Auto);
+        }
+        match value.parse() {
+            Ok(0) | Err(_) => Err(format!("expected a port between 1 and 65535 or 'auto', got '{}'", value)),
+            Ok(port) => Ok(Self::Fixed(port)),
+        }
+    }
+}
+
+impl fmt::Display for PortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fixed(port) => write!(f, "{}", port),
+            Self::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+// Whether a server could listen on `bind`:`port` right now
+pub fn is_free(bind: &str, port: u16) -> bool {
+    TcpListener::bind((bind, port)).is_ok()
+}
+
+// First free port in `start`..`start + AUTO_RANGE`
+pub fn next_free(bind: &str, start: u16) -> Option<u16> {
+    (start..=start.saturating_add(AUTO_RANGE - 1)).find(|&port| is_free(bind, port))
+}
+
+// next_free, failing with a usage error when the whole range is taken
+pub fn pick_free(bind: &str, start: u16) -> Result<u16> {
+    next_free(bind, start).ok_or_else(|| {
+        DocToolsError::Usage(format!(
+            "no free port in {}-{} on {}",
+            start,
+            start.saturating_add(AUTO_RANGE - 1),
+            bind
+        ))
+    })
+}
+
+// Programs whose servers startup may stop without --force
+const DOC_SERVERS: [&str; 3] = ["mkdocs", "mike", "http.server"];
+
+// A process listening on a port
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listener {
+    pub pid: u32,
+    pub uid: u32,
+    // Short command name from /proc/<pid>/comm
+    pub name: String,
+    // Full command line, arguments separated by spaces
+    pub cmdline: String,
+}
+
+impl Listener {
+    // Whether this is a documentation server such as `mkdocs serve`,
+    // `mike serve`, `python -m http.server`, `doc-cli serve` or a
+    // `doc-cli startup` serving a draft on its own thread, judged by
+    // the program and, for python, the module or script it runs; other
+    // arguments are ignored so `vim mkdocs.yml` never counts
+    pub fn is_doc_server(&self) -> bool {
+        let args: Vec<&str> = self.cmdline.split_whitespace().collect();
+        let Some((first, mut rest)) = args.split_first() else {
+            return false;
+        };
+        let mut program = basename(first);
+
+        // Console scripts such as mkdocs show up as `python3 /path/to/mkdocs
+        // serve`; interpreter options may come first, as in `python3 -u -m mkdocs`
+        if program.starts_with("python") {
+            while let Some((arg, after)) = rest.split_first() {
+                rest = after;
+                if *arg == "-m" {
+                    program = rest.first().copied().unwrap_or_default();
+                    break;
+                }
+                if !arg.starts_with('-') {
+                    program = basename(arg);
+                    break;
+                }
+            }
+        }
+
+        DOC_SERVERS.contains(&program)
+            || (program == "doc-cli" && matches!(subcommand(rest), Some("serve" | "startup")))
+    }
+
+    // Login name of the owner, or the uid if it has none
+    pub fn owner(&self) -> String {
+        let passwd = fs::read_to_string("/etc/passwd").unwrap_or_default();
+        user_name(&passwd, self.uid).unwrap_or_else(|| self.uid.to_string())
+    }
+}
+
+impl fmt::Display for Listener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = if self.cmdline.is_empty() { &self.name } else { &self.cmdline };
+        write!(f, "pid {} ({}): {}", self.pid, self.owner(), command)
+    }
+}
+
+// The doc-cli subcommand in the arguments after the program, skipping the
+// global options before it, e.g. "startup" in `doc-cli -C docs startup`
+fn subcommand<'a>(args: &[&'a str]) -> Option<&'a str> {
+    let command = Cli::command();
+    let takes_value = |flag: &str| {
+        command.get_arguments().any(|arg| {
+            arg.get_action().takes_values()
+                && (arg.get_long().is_some_and(|long| flag == format!("--{}", long))
+                    || arg.get_short().is_some_and(|short| flag == format!("-{}", short)))
+        })
+    };
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            return Some(arg);
+        }
+        if takes_value(arg) {
+            args.next();
+        }
+    }
+    None
+}
+
+fn basename(path: &str) -> &str {
+    Path::new(path).file_name().and_then(|name| name.to_str()).unwrap_or(path)
+}
+
+// Name of `uid` in the contents of /etc/passwd
+pub fn user_name(passwd: &str, uid: u32) -> Option<String> {
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.get(2)?.parse::<u32>().ok()? == uid).then(|| fields[0].to_string())
+    })
+}
+
+// Whether `pid` still runs; zombies count as gone since they hold no sockets
+pub fn is_running(pid: u32) -> bool {
+    match fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        // The state follows the parenthesized command name
+        Ok(stat) => stat.rsplit_once(") ").is_some_and(|(_, rest)| !rest.starts_with('Z')),
+        Err(_) => false,
+    }
+}
+
+// A listening socket found in /proc/net/tcp
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Socket {
+    pub uid: u32,
+    pub inode: u64,
+}
+
+// Sockets in LISTEN state on `port` in the contents of /proc/net/tcp or tcp6
+pub fn listening_sockets(table: &str, port: u16) -> Vec<Socket> {
+    // st 0A is TCP_LISTEN
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let local_port = fields.get(1)?.rsplit(':').next()?;
+
+            if u16::from_str_radix(local_port, 16).ok()? != port || *fields.get(3)? != "0A" {
+                return None;
+            }
+            Some(Socket {
+                uid: fields.get(7)?.parse().ok()?,
+                inode: fields.get(9)?.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+// Processes listening on `port`, as far as /proc lets us see them
+//
+// Sockets of other users' processes are found, but not which process holds
+// them unless we may read that process's fds.
+pub fn listeners(port: u16) -> Vec<Listener> {
+    let sockets: Vec<Socket> = ["/proc/net/tcp", "/proc/net/tcp6"]
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|table| listening_sockets(&table, port))
+        .collect();
+    if sockets.is_empty() {
+        return Vec::new();
+    }
+
+    let Ok(processes) = fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut found = Vec::new();
+    for entry in processes.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+
+        let held = socket_inodes(&entry.path());
+        if let Some(socket) = sockets.iter().find(|socket| held.contains(&socket.inode)) {
+            let name = fs::read_to_string(entry.path().join("comm")).unwrap_or_default();
+            found.push(Listener {
+                pid,
+                uid: socket.uid,
+                name: name.trim().to_string(),
+                cmdline: command_line(&entry.path()),
+            });
+        }
+    }
+    found
+}
+
+// Inodes of the sockets a process has open, empty if we may not look
+fn socket_inodes(process: &Path) -> Vec<u64> {
+    let Ok(fds) = fs::read_dir(process.join("fd")) else {
+        return Vec::new();
+    };
+
+    fds.flatten()
+        .filter_map(|fd| fs::read_link(fd.path()).ok())
+        .filter_map(|target| {
+            let target = target.to_string_lossy().into_owned();
+            target.strip_prefix("socket:[")?.strip_suffix(']')?.parse().ok()
+        })
+        .collect()
+}
+
+// /proc/<pid>/cmdline with the NUL separators turned into spaces
+fn command_line(process: &Path) -> String {
+    let raw = fs::read(process.join("cmdline")).unwrap_or_default();
+    String::from_utf8_lossy(&raw)
+        .split('\0')
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
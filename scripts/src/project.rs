@@ -0,0 +1,107 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{DocToolsError, Result};
+
+// Location of the documentation project every tool operates on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    root: PathBuf,
+}
+
+impl Project {
+    // Use an explicit root, or discover one from the current directory
+    pub fn resolve(override_root: Option<&Path>) -> Result<Self> {
+        match override_root {
+            Some(root) => Self::at(root),
+            None => {
+                let current_dir = env::current_dir()
+                    .map_err(|e| DocToolsError::io("Failed to get current directory", e))?;
+                Self::discover(&current_dir)
+            }
+        }
+    }
+
+    // Use the given directory as the project root
+    pub fn at(root: &Path) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .map_err(|e| DocToolsError::io(format!("Invalid project root {}", root.display()), e))?;
+
+        if !root.is_dir() {
+            return Err(DocToolsError::ProjectNotFound(root));
+        }
+
+        Ok(Self { root })
+    }
+
+    // Walk up from `start` to the nearest mkdocs.yml, falling back to the git toplevel
+    pub fn discover(start: &Path) -> Result<Self> {
+        if let Some(dir) = start.ancestors().find(|dir| dir.join("mkdocs.yml").is_file()) {
+            return Self::at(dir);
+        }
+
+        match Self::git_toplevel(start) {
+            Some(toplevel) => Self::at(&toplevel),
+            None => Err(DocToolsError::ProjectNotFound(start.to_path_buf())),
+        }
+    }
+
+    // Ask git for the top of the working tree containing `dir`
+    fn git_toplevel(dir: &Path) -> Option<PathBuf> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .current_dir(dir)
+            .output()
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+
+        let toplevel = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!toplevel.is_empty()).then(|| PathBuf::from(toplevel))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn mkdocs_yml(&self) -> PathBuf {
+        self.root.join("mkdocs.yml")
+    }
+
+    pub fn requirements_txt(&self) -> PathBuf {
+        self.root.join("requirements.txt")
+    }
+
+    pub fn setup_py(&self) -> PathBuf {
+        self.root.join("setup.py")
+    }
+
+    pub fn versions_json(&self) -> PathBuf {
+        self.root.join("versions.json")
+    }
+
+    pub fn docs_dir(&self) -> PathBuf {
+        self.root.join("docs")
+    }
+
+    pub fn site_dir(&self) -> PathBuf {
+        self.root.join("site")
+    }
+
+    pub fn env_file(&self) -> PathBuf {
+        self.root.join(".env")
+    }
+
+    pub fn env_example(&self) -> PathBuf {
+        self.root.join(".env.example")
+    }
+
+    // Directory holding the doc-tools cargo project
+    pub fn scripts_dir(&self) -> PathBuf {
+        self.root.join("scripts")
+    }
+}
@@ -0,0 +1,244 @@
+// Rebuilding the doc-tools binaries when their sources change
+//
+// A successful build records a fingerprint of the crate sources next to the
+// release binaries. The sources are hashed rather than compared by mtime
+// because checkouts and `git pull` rewrite mtimes of unchanged files.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::fingerprint::Fnv1a;
+use crate::{debug, info, warn};
+
+// Written to the release directory after each successful build
+pub const FINGERPRINT_FILE: &str = ".doc-tools-fingerprint";
+
+// How the release binaries relate to the current sources
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Freshness {
+    // Built from exactly the current sources
+    Fresh,
+    // At least one binary has not been built yet
+    Missing,
+    // Built without recording a fingerprint, e.g. by running cargo directly
+    Unrecorded,
+    // The sources changed since the last build
+    Changed,
+}
+
+impl fmt::Display for Freshness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            Self::Fresh => "binaries are up to date",
+            Self::Missing => "binaries have not been built yet",
+            Self::Unrecorded => "no build fingerprint was recorded",
+            Self::Changed => "sources changed since the last build",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+// Result of `doc-cli self rebuild`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RebuildReport {
+    pub crate_dir: PathBuf,
+    pub freshness: Freshness,
+    pub rebuilt: bool,
+    pub fingerprint: String,
+}
+
+// The doc-tools crate and where its release binaries are built
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sources {
+    crate_dir: PathBuf,
+    target_dir: PathBuf,
+    release_dir: PathBuf,
+}
+
+impl Sources {
+    // Sources of the crate at `crate_dir`, built into `target_dir`
+    pub fn with_target_dir(crate_dir: &Path, target_dir: &Path) -> Self {
+        Self {
+            crate_dir: crate_dir.to_path_buf(),
+            target_dir: target_dir.to_path_buf(),
+            release_dir: target_dir.join("release"),
+        }
+    }
+
+    // Find the sources of the running binary: the crate above the executable
+    // (target/release/doc-cli), falling back to where it was compiled
+    pub fn locate() -> Result<Self> {
+        let exe = env::current_exe().map_err(|e| DocToolsError::io("Failed to locate the running binary", e))?;
+        let compiled_in = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+        let crate_dir = exe
+            .ancestors()
+            .skip(1)
+            .find(|dir| is_doc_tools_crate(dir))
+            .or_else(|| is_doc_tools_crate(compiled_in).then_some(compiled_in))
+            .ok_or_else(|| DocToolsError::MissingFile(compiled_in.join("Cargo.toml")))?;
+
+        // The same target directory cargo itself would pick
+        let target_dir = env::var_os("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| crate_dir.join("target"));
+        Ok(Self::with_target_dir(crate_dir, &target_dir))
+    }
+
+    pub fn crate_dir(&self) -> &Path {
+        &self.crate_dir
+    }
+
+    pub fn release_dir(&self) -> &Path {
+        &self.release_dir
+    }
+
+    // Hash of the manifest, lock file and every file under src/
+    pub fn fingerprint(&self) -> Result<String> {
+        let mut files = vec![self.crate_dir.join("Cargo.toml"), self.crate_dir.join("Cargo.lock")];
+        collect_files(&self.crate_dir.join("src"), &mut files)?;
+        files.sort();
+
+        let mut hasher = Fnv1a::new();
+        for file in files.iter().filter(|file| file.is_file()) {
+            let contents = fs::read(file).map_err(|e| DocToolsError::io(format!("Failed to read {}", file.display()), e))?;
+            let relative = file.strip_prefix(&self.crate_dir).unwrap_or(file);
+
+            hasher.write(relative.to_string_lossy().as_bytes());
+            hasher.write_u8(0);
+            hasher.write(&contents);
+        }
+        Ok(hasher.hex())
+    }
+
+    // Compare the release binaries against the current sources
+    pub fn freshness(&self) -> Result<Freshness> {
+        let fingerprint = self.fingerprint()?;
+        Ok(self.freshness_of(&fingerprint))
+    }
+
+    fn freshness_of(&self, fingerprint: &str) -> Freshness {
+        if !self.binaries().iter().all(|binary| binary.is_file()) {
+            return Freshness::Missing;
+        }
+
+        match fs::read_to_string(self.release_dir.join(FINGERPRINT_FILE)) {
+            Ok(recorded) if recorded.trim() == fingerprint => Freshness::Fresh,
+            Ok(_) => Freshness::Changed,
+            Err(_) => Freshness::Unrecorded,
+        }
+    }
+
+    // Release binaries, one per file in src/bin
+    pub fn binaries(&self) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(self.crate_dir.join("src").join("bin")) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .filter_map(|path| path.file_stem().map(|stem| self.release_dir.join(stem)))
+            .map(|binary| binary.with_extension(env::consts::EXE_EXTENSION))
+            .collect()
+    }
+
+    // Build the release binaries through cargo, unless `force` is false and
+    // they are already fresh
+    pub fn rebuild(&self, runner: &dyn CommandRunner, force: bool, dry_run: bool) -> Result<RebuildReport> {
+        let fingerprint = self.fingerprint()?;
+        let freshness = self.freshness_of(&fingerprint);
+        debug!("Sources fingerprint {}: {}", fingerprint, freshness);
+
+        let mut report = RebuildReport {
+            crate_dir: self.crate_dir.clone(),
+            freshness,
+            rebuilt: false,
+            fingerprint,
+        };
+
+        if !force && freshness == Freshness::Fresh {
+            info!("doc-tools is up to date");
+            return Ok(report);
+        }
+
+        if force {
+            info!("Rebuilding doc-tools...");
+        } else {
+            info!("Rebuilding doc-tools: {}", freshness);
+        }
+
+        // An explicit --target-dir, so cargo builds where the fingerprint goes
+        let manifest = self.crate_dir.join("Cargo.toml");
+        let cmd = Cmd::new("cargo", &self.crate_dir)
+            .args(["build", "--release", "--manifest-path"])
+            .arg(manifest.to_string_lossy())
+            .arg("--target-dir")
+            .arg(self.target_dir.to_string_lossy())
+            .mutating();
+
+        let output = runner
+            .run(&cmd)
+            .map_err(|e| DocToolsError::Build(format!("failed to run cargo: {}", e)))?;
+        if !output.success() {
+            return Err(DocToolsError::Build(format!("'{}' failed", cmd)));
+        }
+
+        if !dry_run {
+            let path = self.release_dir.join(FINGERPRINT_FILE);
+            fs::write(&path, format!("{}\n", report.fingerprint))
+                .map_err(|e| DocToolsError::io(format!("Failed to write {}", path.display()), e))?;
+        }
+
+        info!("doc-tools rebuilt in {}", self.release_dir.display());
+        report.rebuilt = true;
+        Ok(report)
+    }
+}
+
+// Warn when the running binary is a release build older than its sources
+pub fn warn_if_stale() {
+    let Ok(sources) = Sources::locate() else {
+        return;
+    };
+
+    // Debug builds from `cargo run` are rebuilt by cargo itself
+    let in_release_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .zip(sources.release_dir.canonicalize().ok())
+        .is_some_and(|(exe_dir, release_dir)| exe_dir == release_dir);
+
+    if in_release_dir && sources.freshness().ok() == Some(Freshness::Changed) {
+        warn!("This build of doc-tools is older than its sources; run 'doc-cli self rebuild' to update it");
+    }
+}
+
+fn is_doc_tools_crate(dir: &Path) -> bool {
+    fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|manifest| manifest.contains("name = \"doc-tools\""))
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = fs::read_dir(dir).map_err(|e| DocToolsError::io(format!("Failed to read {}", dir.display()), e))?;
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| DocToolsError::io(format!("Failed to read {}", dir.display()), e))?
+            .path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -1,266 +1,576 @@
 use std::env;
-use std::io::{self, Write};
-use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-
-// Main entry point
-fn main() {
-    // Parse command line arguments
-    let args: Vec<String> = env::args().collect();
-    let mut draft_version = None;
-    
-    // Check for --draft-version argument
-    for i in 1..args.len() {
-        if args[i] == "--draft-version" && i + 1 < args.len() {
-            draft_version = Some(args[i + 1].clone());
-            break;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::context::Context;
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::log::{self, Phase};
+use crate::port::{self, PortSpec};
+use crate::project::Project;
+use crate::static_server::StaticServer;
+use crate::supervisor::{Service, Start, Supervisor};
+use crate::venv::Venv;
+use crate::{debug, info, prompt, say, warn};
+
+// Where the server's output is kept, relative to the project root
+pub const SERVER_LOG: &str = ".doc-tools/server.log";
+
+// How long processes holding the port get to exit after SIGTERM
+const TERM_TIMEOUT: Duration = Duration::from_secs(5);
+
+// How often to check whether they exited and the port is free
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Where the tools run, which only changes startup's defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    // A GitHub Codespace, which forwards the port from outside the container
+    Codespaces,
+    // A laptop or any other machine
+    Local,
+}
+
+impl Environment {
+    pub fn detect() -> Self {
+        if env::var("CODESPACES").is_ok() {
+            Self::Codespaces
+        } else {
+            Self::Local
+        }
+    }
+
+    // Address the server listens on: every interface so Codespaces can
+    // forward the port, loopback only everywhere else
+    pub fn default_bind(self) -> &'static str {
+        match self {
+            Self::Codespaces => "0.0.0.0",
+            Self::Local => "127.0.0.1",
         }
     }
-    
-    let startup = Startup::new(draft_version);
-    startup.run();
+}
+
+// Where the documentation server can be reached, also the `--output json`
+// result of `startup`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartupReport {
+    pub environment: Environment,
+    pub url: String,
+    pub bind: String,
+    pub port: u16,
+    pub pid: Option<u32>,
+    pub draft_version: Option<String>,
+    // Whether the site answered before startup stopped waiting for it
+    pub ready: bool,
+    pub log: Option<PathBuf>,
+}
+
+// A running documentation server
+#[derive(Debug)]
+pub struct Server {
+    environment: Environment,
+    url: String,
+    bind: String,
+    port: u16,
+    draft_version: Option<String>,
+    start: Start,
+    supervisor: Supervisor,
+}
+
+impl Server {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.supervisor.pid()
+    }
+
+    pub fn report(&self) -> StartupReport {
+        StartupReport {
+            environment: self.environment,
+            url: self.url.clone(),
+            bind: self.bind.clone(),
+            port: self.port,
+            pid: self.pid(),
+            draft_version: self.draft_version.clone(),
+            ready: self.start == Start::Ready,
+            log: self.supervisor.log().map(PathBuf::from),
+        }
+    }
+
+    // Block until the server exits, restarting it whenever it crashes
+    pub fn wait(self) -> Result<()> {
+        self.supervisor.supervise()
+    }
 }
 
 // Startup struct to encapsulate the functionality
-struct Startup {
-    project_root: PathBuf,
+pub struct Startup {
+    project: Project,
+    config: Config,
+    runner: Arc<dyn CommandRunner>,
     draft_version: Option<String>,
+    environment: Environment,
+    port: Option<PortSpec>,
+    bind: Option<String>,
+    recreate_venv: bool,
+    reinstall: bool,
+    force: bool,
+    dry_run: bool,
 }
 
 impl Startup {
-    // Create a new Startup instance
-    fn new(draft_version: Option<String>) -> Self {
-        // Get the project root directory (current directory, since we're running from project root)
-        let current_dir = env::current_dir().expect("Failed to get current directory");
-        let project_root = if current_dir.ends_with("scripts") {
-            current_dir.parent().unwrap().to_path_buf()
-        } else {
-            current_dir
-        };
-        println!("Debug - Project root: {}", project_root.display());
-        
-        Self { project_root, draft_version }
+    // Create a new Startup instance for the detected environment
+    pub fn new(ctx: &Context, draft_version: Option<String>) -> Self {
+        Self {
+            project: ctx.project.clone(),
+            config: ctx.config.clone(),
+            runner: Arc::clone(&ctx.runner),
+            draft_version,
+            environment: Environment::detect(),
+            port: None,
+            bind: None,
+            recreate_venv: false,
+            reinstall: false,
+            force: false,
+            dry_run: ctx.dry_run,
+        }
+    }
+
+    // Rebuild .venv from scratch before installing into it
+    pub fn recreate_venv(mut self, recreate: bool) -> Self {
+        self.recreate_venv = recreate;
+        self
+    }
+
+    // Run the pip installs even if nothing changed since the last one
+    pub fn reinstall(mut self, reinstall: bool) -> Self {
+        self.reinstall = reinstall;
+        self
+    }
+
+    // Serve on this port instead of server.port; Auto picks the first free one
+    pub fn port(mut self, port: Option<PortSpec>) -> Self {
+        self.port = port;
+        self
+    }
+
+    // Listen on this address instead of the environment's default
+    pub fn bind(mut self, bind: Option<String>) -> Self {
+        self.bind = bind;
+        self
+    }
+
+    // Also offer to kill port holders that are not documentation servers
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    // Use the defaults of `environment` instead of the detected one
+    pub fn in_environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
     }
 
     // Main execution method
-    fn run(&self) {
-        println!("==== Starting setup for my-life-as-a-dev project ====");
+    pub fn run(&self) -> Result<()> {
+        self.start()?.wait()
+    }
 
-        // Only run in Codespaces environment
-        if !self.is_codespaces_environment() {
-            self.show_local_dev_instructions();
-            return;
+    // Set up and start the server without waiting for it to exit
+    pub fn start(&self) -> Result<Server> {
+        info!("==== Starting setup for my-life-as-a-dev project ====");
+
+        match self.environment {
+            Environment::Codespaces => {
+                info!("GitHub Codespaces environment detected! Setting up development environment...")
+            }
+            Environment::Local => info!("Setting up local development environment..."),
         }
-        
-        println!("GitHub Codespaces environment detected! Setting up development environment...");
-        
-        self.install_dependencies();
-        self.check_port_and_kill_if_needed();
-        self.start_documentation_server();
-        
-        self.show_completion_message();
+
+        self.install_dependencies()?;
+        let port = self.check_port_and_kill_if_needed()?;
+        let server = self.start_documentation_server(port)?;
+
+        self.show_completion_message(&server);
+        Ok(server)
     }
 
-    // Check if we're in GitHub Codespaces
-    fn is_codespaces_environment(&self) -> bool {
-        env::var("CODESPACES").is_ok()
+    // Command run from the project root
+    fn cmd(&self, program: &str) -> Cmd {
+        Cmd::new(program, self.project.root())
     }
 
-    // Show instructions for local development
-    fn show_local_dev_instructions(&self) {
-        println!("This script is optimized for GitHub Codespaces.");
-        println!("For local development, please follow the instructions in the README:");
-        println!("https://github.com/BA-CalderonMorales/my-life-as-a-dev#local-development");
+    // The project's venv that pip installs into
+    fn venv(&self) -> Venv {
+        Venv::at(&self.project)
     }
 
-    // Install dependencies from requirements.txt
-    fn install_dependencies(&self) {
-        let requirements_path = self.project_root.join("requirements.txt");
-        
-        println!("Installing dependencies from {}...", requirements_path.display());
-        
-        // Debug: Print the actual path being used
-        println!("Debug - Requirements path: {}", requirements_path.display());
-        
+    // The venv's interpreter, used for pip, mkdocs and the draft server
+    fn python(&self) -> String {
+        self.venv().python().to_string_lossy().into_owned()
+    }
+
+    // Port asked for, server.port unless --port was given
+    fn port_spec(&self) -> PortSpec {
+        self.port.unwrap_or(PortSpec::Fixed(self.config.port.value))
+    }
+
+    // Address the server listens on
+    fn bind_address(&self) -> &str {
+        self.bind.as_deref().unwrap_or(self.environment.default_bind())
+    }
+
+    // Where the readiness probe reaches the server
+    fn probe(&self, port: u16) -> (String, u16) {
+        let host = match self.bind_address() {
+            "0.0.0.0" => "127.0.0.1",
+            "::" => "::1",
+            bind => bind,
+        };
+        (host.to_string(), port)
+    }
+
+    // Address to open in a browser; Codespaces forwards the port to its own domain
+    fn url(&self, port: u16) -> String {
+        let forwarded = match self.environment {
+            Environment::Codespaces => env::var("CODESPACE_NAME")
+                .ok()
+                .zip(env::var("GITHUB_CODESPACES_PORT_FORWARDING_DOMAIN").ok()),
+            Environment::Local => None,
+        };
+
+        match forwarded {
+            Some((name, domain)) => format!("https://{}-{}.{}", name, port, domain),
+            None if self.bind_address() == "0.0.0.0" => format!("http://localhost:{}", port),
+            None => format!("http://{}:{}", self.bind_address(), port),
+        }
+    }
+
+    // Install dependencies from requirements.txt into the project's venv
+    pub fn install_dependencies(&self) -> Result<()> {
+        log::set_phase(Phase::Install);
+        let requirements_path = self.project.requirements_txt();
+        let venv = self.venv();
+
+        venv.ensure(self.runner.as_ref(), self.project.root(), &self.config, self.recreate_venv)?;
+
+        debug!("Requirements path: {}", requirements_path.display());
+
         // Verify the file exists before attempting to install
-        if !Path::new(&requirements_path).exists() {
-            eprintln!("Error: Requirements file not found at {}", requirements_path.display());
-            eprintln!("Current directory: {:?}", env::current_dir().unwrap_or_default());
-            std::process::exit(1);
+        if !requirements_path.exists() {
+            return Err(DocToolsError::MissingFile(requirements_path));
+        }
+
+        // A recreated venv is empty, whatever fingerprint it had before
+        let fingerprint = venv.install_fingerprint(&self.project)?;
+        let unchanged = venv.installed_fingerprint().as_deref() == Some(fingerprint.as_str());
+        if unchanged && !self.reinstall && !self.recreate_venv {
+            info!("Dependencies are unchanged since the last install, skipping pip (use --reinstall to force it).");
+            return Ok(());
         }
-        
-        let status = Command::new("python")
-            .args(&["-m", "pip", "install", "-r"])
-            .arg(&requirements_path)
-            .status()
-            .expect("Failed to execute pip install command");
-
-        if !status.success() {
-            eprintln!("Error: Failed to install dependencies.");
-            std::process::exit(1);
+
+        info!("Installing dependencies from {}...", requirements_path.display());
+
+        let install = self
+            .cmd(&self.python())
+            .args(["-m", "pip", "install", "-r"])
+            .arg(requirements_path.to_string_lossy())
+            .mutating();
+
+        if !self.runner.run(&install)?.success() {
+            return Err(DocToolsError::Python("Failed to install dependencies.".to_string()));
         }
-        
-        println!("Dependencies installed successfully.");
-        
+
+        info!("Dependencies installed successfully.");
+
         // Install the project in development mode to ensure plugins are available
-        println!("Installing project in development mode...");
-        let status = Command::new("pip")
-            .args(&["install", "-e", "."])
-            .current_dir(&self.project_root)
-            .status()
-            .expect("Failed to execute pip install -e .");
-            
-        if !status.success() {
-            eprintln!("Error: Failed to install project in development mode.");
-            std::process::exit(1);
+        info!("Installing project in development mode...");
+        let install_editable = self
+            .cmd(&self.python())
+            .args(["-m", "pip", "install", "-e", "."])
+            .mutating();
+
+        if !self.runner.run(&install_editable)?.success() {
+            return Err(DocToolsError::Python(
+                "Failed to install project in development mode.".to_string(),
+            ));
+        }
+
+        info!("Project installed in development mode.");
+
+        // A dry run installed nothing, so there is nothing to remember; failing
+        // to remember only costs a reinstall next time
+        if !self.dry_run {
+            if let Err(e) = venv.record_install(&fingerprint) {
+                warn!("{}", e);
+            }
         }
-        
-        println!("Project installed in development mode.");
+        Ok(())
     }
 
-    // Check if port 8000 is in use and offer to kill the process
-    fn check_port_and_kill_if_needed(&self) {
-        // Check if port 8000 is in use
-        let check_port = Command::new("lsof")
-            .args(&["-Pi", ":8000", "-sTCP:LISTEN", "-t"])
-            .stdout(Stdio::null())
-            .status()
-            .expect("Failed to execute lsof command");
-
-        if !check_port.success() {
-            println!("Port 8000 is available.");
-            return;
+    // Pick the port to serve on: with --port auto the first free one from
+    // server.port up, otherwise the fixed port, offering to kill whatever
+    // already listens on it
+    pub fn check_port_and_kill_if_needed(&self) -> Result<u16> {
+        log::set_phase(Phase::Port);
+        let bind = self.bind_address();
+
+        let port = match self.port_spec() {
+            PortSpec::Auto => {
+                let port = port::pick_free(bind, self.config.port.value)?;
+                info!("Using free port {}.", port);
+                return Ok(port);
+            }
+            PortSpec::Fixed(port) => port,
+        };
+
+        if port::is_free(bind, port) {
+            info!("Port {} is available.", port);
+            return Ok(port);
         }
-        
-        println!("Port 8000 is already in use.");
-        
+
+        info!("Port {} is already in use.", port);
+
         // Show what process is using the port
-        println!("Process using port 8000:");
-        let _ = Command::new("lsof")
-            .args(&["-Pi", ":8000", "-sTCP:LISTEN"])
-            .status()
-            .expect("Failed to execute lsof command");
-        
+        let listeners = port::listeners(port);
+        if listeners.is_empty() {
+            // Starting anyway would only fail later with a bind error from the server
+            return Err(DocToolsError::PortInUse {
+                port,
+                reason: "cannot tell which process holds it, it may belong to another user; \
+                         stop it yourself or use --port auto"
+                    .to_string(),
+            });
+        }
+        say!("Process using port {}:", port);
+        for listener in &listeners {
+            say!("  {}", listener);
+        }
+
+        // Only servers startup could have started itself are fair game
+        let unknown: Vec<String> = listeners
+            .iter()
+            .filter(|listener| !listener.is_doc_server())
+            .map(|listener| format!("pid {} ({})", listener.pid, listener.name))
+            .collect();
+        if !unknown.is_empty() && !self.force {
+            return Err(DocToolsError::PortInUse {
+                port,
+                reason: format!(
+                    "{} does not look like a documentation server; stop it yourself, rerun with --force to kill it or use --port auto",
+                    unknown.join(", ")
+                ),
+            });
+        }
+
         // Ask if user wants to kill the process
-        print!("Do you want to kill this process? (y/n): ");
-        io::stdout().flush().unwrap();
-        
+        let which = if listeners.len() == 1 { "this process" } else { "these processes" };
+        prompt!("Do you want to kill {}? (y/n): ", which);
+
         let mut answer = String::new();
-        io::stdin().read_line(&mut answer).expect("Failed to read input");
-        
+        io::stdin()
+            .read_line(&mut answer)
+            .map_err(|e| DocToolsError::io("Failed to read input", e))?;
+
         if answer.trim().to_lowercase() == "y" {
-            self.kill_process_on_port(8000);
+            self.stop_listeners(port, &listeners)?;
         } else {
-            println!("Port 8000 is still in use. MkDocs server may fail to start.");
+            warn!("Port {} is still in use. MkDocs server may fail to start.", port);
         }
+        Ok(port)
     }
 
-    // Kill the process using the specified port
-    fn kill_process_on_port(&self, port: u16) {
-        println!("Terminating process on port {}...", port);
-        
-        // Get PID and kill it
-        let output = Command::new("lsof")
-            .args(&[&format!("-ti:{}", port)])
-            .output()
-            .expect("Failed to get process ID");
-        
-        if output.stdout.is_empty() {
-            println!("No process found on port {}", port);
-            return;
+    // Stop the processes listening on the port: SIGTERM first, SIGKILL for
+    // whatever is still running after TERM_TIMEOUT, then make sure the port
+    // is free
+    fn stop_listeners(&self, port: u16, listeners: &[port::Listener]) -> Result<()> {
+        info!("Terminating process on port {}...", port);
+
+        let mut running: Vec<u32> = Vec::new();
+        for listener in listeners {
+            if self.signal("TERM", listener.pid)? {
+                running.push(listener.pid);
+            } else {
+                warn!("Failed to signal process {}. You may need to kill it manually.", listener.pid);
+            }
+        }
+        if self.dry_run {
+            return Ok(());
         }
 
-        let pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let status = Command::new("kill")
-            .args(&["-9", &pid])
-            .status()
-            .expect("Failed to terminate process");
-            
-        if status.success() {
-            println!("Process terminated successfully.");
-        } else {
-            println!("Failed to terminate process. You may need to kill it manually.");
+        let deadline = Instant::now() + TERM_TIMEOUT;
+        while running.iter().any(|&pid| port::is_running(pid)) && Instant::now() < deadline {
+            thread::sleep(POLL_INTERVAL);
         }
+
+        running.retain(|&pid| port::is_running(pid));
+        for &pid in &running {
+            warn!("Process {} ignored SIGTERM for {}s, sending SIGKILL.", pid, TERM_TIMEOUT.as_secs());
+            self.signal("KILL", pid)?;
+        }
+
+        // A killed listener's socket is closed by the kernel right away
+        let deadline = Instant::now() + TERM_TIMEOUT;
+        while !port::is_free(self.bind_address(), port) {
+            if Instant::now() >= deadline {
+                return Err(DocToolsError::PortInUse {
+                    port,
+                    reason: "still in use after stopping its processes".to_string(),
+                });
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        info!("Port {} is free now.", port);
+        Ok(())
     }
 
-    // Start the MkDocs development server
-    fn start_documentation_server(&self) {
-        println!("Starting documentation server...");
-        
-        // Change to the project root directory where mkdocs.yml is located
-        if let Err(e) = env::set_current_dir(&self.project_root) {
-            eprintln!("Failed to change to project root directory: {}", e);
-            std::process::exit(1);
+    // Send SIGNAL to pid and report whether kill succeeded
+    fn signal(&self, signal: &str, pid: u32) -> Result<bool> {
+        let kill = self
+            .cmd("kill")
+            .arg(format!("-{}", signal))
+            .arg(pid.to_string())
+            .mutating();
+        Ok(self.runner.run(&kill)?.success())
+    }
+
+    // Build the site into site/ with mkdocs
+    pub fn build_site(&self) -> Result<()> {
+        let build = self
+            .cmd(&self.python())
+            .args(["-m", "mkdocs", "build", "--clean"])
+            .mutating();
+
+        if !self.runner.run(&build)?.success() {
+            return Err(DocToolsError::Python("Failed to build site with mkdocs.".to_string()));
         }
-        
+        Ok(())
+    }
+
+    // Start the MkDocs development server
+    pub fn start_documentation_server(&self, port: u16) -> Result<Server> {
+        log::set_phase(Phase::Serve);
+        info!("Starting documentation server...");
+
         // Verify that the custom plugin is available
-        println!("Verifying plugin installation...");
-        let verify_cmd = Command::new("python")
+        info!("Verifying plugin installation...");
+        let verify_cmd = self
+            .cmd(&self.python())
             .arg("-c")
-            .arg("import sys; import mkdocs_plugins; print(f'Plugin module found at: {mkdocs_plugins.__file__}')")
-            .status();
-            
-        match verify_cmd {
-            Ok(status) if status.success() => println!("Plugin module verification successful."),
-            _ => println!("Warning: Plugin module verification failed. This may cause issues with custom plugins.")
+            .arg("import sys; import mkdocs_plugins; print(f'Plugin module found at: {mkdocs_plugins.__file__}')");
+
+        match self.runner.run(&verify_cmd) {
+            Ok(output) if output.success() => info!("Plugin module verification successful."),
+            _ => warn!("Warning: Plugin module verification failed. This may cause issues with custom plugins."),
         }
-        
+
         // Check if mike is available for versioning by trying to import it
-        let mike_available = Command::new("python")
+        let mike_check = self
+            .cmd(&self.python())
             .arg("-c")
             .arg("import mike; print('mike available')")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .is_ok();
-        
+            .quiet();
+        let mike_available = self.runner.run(&mike_check).is_ok_and(|output| output.success());
+
         if mike_available {
-            println!("Mike is available, but using standard MkDocs serve for simplicity");
+            info!("Mike is available, but using standard MkDocs serve for simplicity");
         }
-        
-        // Determine the serve command based on availability and draft version
-        let cmd_str = if let Some(version) = &self.draft_version {
-            println!("Using draft version: {} (not yet deployed)", version);
-            
+
+        // Determine what serves the site based on the draft version
+        let serve = if let Some(version) = &self.draft_version {
+            info!("Using draft version: {} (not yet deployed)", version);
+
             // Build the site first with mkdocs
-            println!("Building draft documentation for version {}...", version);
-            let build_status = Command::new("python")
-                .args(&["-m", "mkdocs", "build", "--clean"])
-                .status()
-                .expect("Failed to build site with mkdocs");
-                
-            if !build_status.success() {
-                eprintln!("Error: Failed to build site with mkdocs.");
-                std::process::exit(1);
+            info!("Building draft documentation for version {}...", version);
+            self.build_site()?;
+
+            // For draft versions, serve the built site under its version path
+            info!("Serving draft version with the built-in static server...");
+            Service::Static {
+                server: StaticServer::new(self.project.site_dir()).base_path(Some(version)),
+                bind: self.bind_address().to_string(),
+                port,
             }
-            
-            // For draft versions, serve the built site directly
-            println!("Serving draft version using Python HTTP server...");
-            "cd site && python -m http.server 8000 --bind 0.0.0.0".to_string()
         } else {
-            println!("Using standard MkDocs serve");
-            "PYTHONPATH=$PYTHONPATH:$(pwd) python -m mkdocs serve --dev-addr=0.0.0.0:8000".to_string()
+            info!("Using standard MkDocs serve");
+            let cmd_str = format!(
+                "PYTHONPATH=$PYTHONPATH:$(pwd) exec '{}' -m mkdocs serve --dev-addr={}:{}",
+                self.python(),
+                self.bind_address(),
+                port
+            );
+            // exec replaces the shell, so the pid is the server's own
+            let serve = self.cmd("sh").arg("-c").arg(cmd_str).mutating();
+            info!("Executing: {}", serve);
+            Service::Command(serve)
         };
-        
-        println!("Executing: {}", cmd_str);
-        
-        let status = Command::new("sh")
-            .arg("-c")
-            .arg(&cmd_str)
-            .status()
-            .expect("Failed to start documentation server");
-            
-        if !status.success() {
-            eprintln!("Error: Failed to start documentation server.");
-            std::process::exit(1);
-        }
+
+        // A dry run starts nothing, so it has no log to write either. The
+        // static server logs its requests here in the terminal.
+        let logged = !self.dry_run && matches!(serve, Service::Command(_));
+        let log = logged.then(|| self.project.root().join(SERVER_LOG));
+        let url = match &self.draft_version {
+            Some(version) => format!("{}/{}/", self.url(port), version),
+            None => self.url(port),
+        };
+        let mut supervisor = Supervisor::new(Arc::clone(&self.runner), serve, self.probe(port), log)
+            .url(url.as_str())
+            .dry_run(self.dry_run);
+        let start = supervisor.start()?;
+
+        Ok(Server {
+            environment: self.environment,
+            url,
+            bind: self.bind_address().to_string(),
+            port,
+            draft_version: self.draft_version.clone(),
+            start,
+            supervisor,
+        })
     }
 
     // Show completion message
-    fn show_completion_message(&self) {
-        println!("==== Setup complete! ====");
-        println!("Your versioned documentation is now available at http://localhost:8000");
-        println!("You can start editing the files in the 'docs/' directory.");
-        println!("Changes will be reflected automatically on the development server.");
+    fn show_completion_message(&self, server: &Server) {
+        match server.start {
+            Start::Ready => {}
+            Start::NotReady => {
+                let output = match server.supervisor.log() {
+                    Some(log) => format!(" Its output is in {}.", log.display()),
+                    None => String::new(),
+                };
+                warn!(
+                    "The documentation server is not answering yet; it may still be building. \
+                     The URL is shown as soon as it does.{}",
+                    output
+                );
+                return;
+            }
+            Start::Exited if self.dry_run => {}
+            Start::Exited => {
+                warn!("The documentation server exited right after starting.");
+                return;
+            }
+        }
+
+        info!("==== Setup complete! ====");
+        info!("Your versioned documentation is now available at {}", server.url());
+        if self.environment == Environment::Codespaces {
+            info!("Codespaces forwards port {}; open it from the Ports tab if the link does not load.", server.port);
+        }
+        info!("You can start editing the files in the 'docs/' directory.");
+        info!("Changes will be reflected automatically on the development server.");
+        if let Some(log) = server.supervisor.log() {
+            info!("Server output is kept in {}; the server restarts by itself if it crashes.", log.display());
+        }
     }
-}
\ No newline at end of file
+}
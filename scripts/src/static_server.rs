@@ -0,0 +1,562 @@
+// A small static file server for built sites, behind `doc-cli serve`
+//
+// Startup runs it on a thread for draft previews instead of spawning
+// `python -m http.server`. It
+// sends proper content types, compresses text with gzip, revalidates with
+// ETags instead of letting browsers cache stale builds, falls back to the
+// site's 404.html and can serve the site under a version path the way mike
+// deploys it. Every request is logged.
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, UNIX_EPOCH};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::error::{DocToolsError, Result};
+use crate::exec::{CommandOutput, Process};
+use crate::{debug, info};
+
+// Smaller bodies are not worth compressing
+const GZIP_MIN_SIZE: usize = 256;
+
+// Longest request line or header line accepted
+const MAX_LINE: u64 = 8 * 1024;
+
+// Connections answered at the same time; further ones wait to be accepted
+const WORKERS: usize = 8;
+
+// Content types by file extension; anything else is application/octet-stream
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("html", "text/html; charset=utf-8"),
+    ("htm", "text/html; charset=utf-8"),
+    ("css", "text/css; charset=utf-8"),
+    ("js", "text/javascript; charset=utf-8"),
+    ("mjs", "text/javascript; charset=utf-8"),
+    ("json", "application/json"),
+    ("map", "application/json"),
+    ("xml", "application/xml"),
+    ("txt", "text/plain; charset=utf-8"),
+    ("md", "text/markdown; charset=utf-8"),
+    ("yml", "text/yaml; charset=utf-8"),
+    ("yaml", "text/yaml; charset=utf-8"),
+    ("svg", "image/svg+xml"),
+    ("png", "image/png"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+    ("avif", "image/avif"),
+    ("ico", "image/x-icon"),
+    ("woff", "font/woff"),
+    ("woff2", "font/woff2"),
+    ("ttf", "font/ttf"),
+    ("otf", "font/otf"),
+    ("eot", "application/vnd.ms-fontobject"),
+    ("pdf", "application/pdf"),
+    ("wasm", "application/wasm"),
+    ("mp4", "video/mp4"),
+    ("webm", "video/webm"),
+    ("gz", "application/gzip"),
+    ("zip", "application/zip"),
+];
+
+// Content type for a file name
+pub fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+
+    CONTENT_TYPES
+        .iter()
+        .find(|(known, _)| *known == extension)
+        .map_or("application/octet-stream", |(_, content_type)| content_type)
+}
+
+// Text compresses well; images, fonts and archives already are compressed
+fn compressible(content_type: &str) -> bool {
+    content_type.starts_with("text/")
+        || ["application/json", "application/xml", "image/svg+xml", "application/wasm"]
+            .iter()
+            .any(|known| content_type.starts_with(known))
+}
+
+// An HTTP request as far as a static server cares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    // Path and query exactly as sent
+    pub target: String,
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    pub fn new(method: &str, target: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            target: target.to_string(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // Read a request head; None if the client sent nothing
+    pub fn read(reader: &mut impl BufRead) -> io::Result<Option<Self>> {
+        let Some(line) = read_line(reader)? else {
+            return Ok(None);
+        };
+
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "malformed request line"));
+        };
+        let mut request = Self::new(method, target);
+
+        while let Some(line) = read_line(reader)? {
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                request = request.header(name.trim(), value.trim());
+            }
+        }
+        Ok(Some(request))
+    }
+
+    // Value of the first header called `name`, ignoring case
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn accepts_gzip(&self) -> bool {
+        self.get("Accept-Encoding").is_some_and(|codings| {
+            codings.split(',').any(|coding| {
+                let mut params = coding.split(';').map(str::trim);
+                params.next() == Some("gzip") && !params.any(|param| param == "q=0")
+            })
+        })
+    }
+}
+
+// One line without its line ending, None at end of input
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if (&mut *reader).take(MAX_LINE).read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+}
+
+// A complete response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_string(), value.into()));
+        self
+    }
+
+    fn with_body(mut self, content_type: &str, body: Vec<u8>) -> Self {
+        self.body = body;
+        self.with_header("Content-Type", content_type)
+    }
+
+    fn text(status: u16, message: &str) -> Self {
+        Self::new(status).with_body("text/plain; charset=utf-8", format!("{}\n", message).into_bytes())
+    }
+
+    fn redirect(status: u16, location: &str) -> Self {
+        Self::new(status).with_header("Location", location)
+    }
+
+    // Value of the first header called `name`, ignoring case
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Send the response; HEAD requests get the headers only
+    pub fn write_to(&self, out: &mut impl Write, head_only: bool) -> io::Result<()> {
+        let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, reason(self.status));
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len()));
+
+        out.write_all(head.as_bytes())?;
+        if !head_only {
+            out.write_all(&self.body)?;
+        }
+        out.flush()
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+// Serves the files below a directory
+#[derive(Debug, Clone)]
+pub struct StaticServer {
+    root: PathBuf,
+    // "/" or "/<path>/"
+    base: String,
+}
+
+impl StaticServer {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            base: "/".to_string(),
+        }
+    }
+
+    // Serve the site under /<path>/, e.g. the version a draft would be deployed as
+    pub fn base_path(mut self, path: Option<&str>) -> Self {
+        self.base = match path.map(|path| path.trim_matches('/')) {
+            Some(path) if !path.is_empty() => format!("/{}/", path),
+            _ => "/".to_string(),
+        };
+        self
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    // Accept connections on bind:port until the process ends
+    pub fn listen(&self, bind: &str, port: u16) -> Result<()> {
+        self.spawn(bind, port)?.wait()?;
+        Ok(())
+    }
+
+    // Serve on bind:port from a background thread until the returned handle
+    // is stopped or dropped; a port that is taken fails here, not later
+    pub fn spawn(&self, bind: &str, port: u16) -> Result<Serving> {
+        let listener = TcpListener::bind((bind, port))
+            .map_err(|e| DocToolsError::io(format!("Failed to listen on {}:{}", bind, port), e))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| DocToolsError::io(format!("Failed to listen on {}:{}", bind, port), e))?;
+        info!("Serving {} at http://{}:{}{}", self.root.display(), bind, port, self.base);
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let accept = {
+            let server = self.clone();
+            let stop = Arc::clone(&stop);
+            thread::spawn(move || server.accept(listener, &stop))
+        };
+
+        Ok(Serving {
+            addr,
+            stop,
+            accept: Some(accept),
+        })
+    }
+
+    fn accept(&self, listener: TcpListener, stop: &AtomicBool) {
+        // A fixed pool of workers, so a flood of connections cannot start a
+        // thread each; the queue only holds as many as there are workers.
+        // Dropping the queue once accepting stops ends the workers.
+        let (queue, connections) = mpsc::sync_channel::<TcpStream>(WORKERS);
+        let connections = Arc::new(Mutex::new(connections));
+        for _ in 0..WORKERS {
+            let server = self.clone();
+            let connections = Arc::clone(&connections);
+            thread::spawn(move || loop {
+                let next = match connections.lock() {
+                    Ok(connections) => connections.recv(),
+                    Err(_) => return,
+                };
+                match next {
+                    Ok(stream) => server.handle(stream),
+                    Err(_) => return,
+                }
+            });
+        }
+
+        for stream in listener.incoming() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(stream) => {
+                    if queue.send(stream).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => debug!("Failed to accept a connection: {}", e),
+            }
+        }
+    }
+
+    // Answer one request on a connection and log it
+    fn handle(&self, mut stream: TcpStream) {
+        let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+        let peer = stream.peer_addr().map(|addr| addr.ip().to_string()).unwrap_or_default();
+
+        let request = match Request::read(&mut BufReader::new(&stream)) {
+            Ok(Some(request)) => request,
+            Ok(None) => return,
+            Err(e) => {
+                debug!("Bad request from {}: {}", peer, e);
+                let _ = Response::text(400, "Bad Request").write_to(&mut stream, false);
+                return;
+            }
+        };
+
+        let response = self.respond(&request);
+        let head_only = request.method == "HEAD";
+        if let Err(e) = response.write_to(&mut stream, head_only) {
+            debug!("Failed to answer {}: {}", peer, e);
+        }
+        info!(
+            "{} \"{} {}\" {} {}",
+            peer,
+            request.method,
+            request.target,
+            response.status,
+            if head_only { 0 } else { response.body.len() }
+        );
+    }
+
+    // Work out the response to a request
+    pub fn respond(&self, request: &Request) -> Response {
+        if request.method != "GET" && request.method != "HEAD" {
+            return Response::text(405, "Method Not Allowed").with_header("Allow", "GET, HEAD");
+        }
+
+        let raw_path = request.target.split(['?', '#']).next().unwrap_or("/");
+        let Some(path) = percent_decode(raw_path) else {
+            return Response::text(400, "Bad Request");
+        };
+
+        // The version path itself, and the root when the site lives below it
+        if self.base != "/" && (path == "/" || path == self.base.trim_end_matches('/')) {
+            return Response::redirect(302, &self.base);
+        }
+        let Some(relative) = path.strip_prefix(&self.base) else {
+            return self.not_found(request);
+        };
+
+        let mut file = self.root.clone();
+        for segment in relative.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => return Response::text(400, "Bad Request"),
+                _ if segment.contains('\\') || segment.contains('\0') => return Response::text(400, "Bad Request"),
+                _ => file.push(segment),
+            }
+        }
+
+        if file.is_dir() {
+            // Relative links in index.html only work with the trailing slash
+            if !path.ends_with('/') {
+                return Response::redirect(301, &format!("{}/", raw_path));
+            }
+            file.push("index.html");
+        }
+
+        if !file.is_file() {
+            return self.not_found(request);
+        }
+        if !self.contains(&file) {
+            return Response::text(403, "Forbidden");
+        }
+        self.file(request, &file, 200)
+    }
+
+    // Whether `path` lies inside the site once symlinks are resolved, so a
+    // link in the build output cannot expose files elsewhere
+    fn contains(&self, path: &Path) -> bool {
+        match (self.root.canonicalize(), path.canonicalize()) {
+            (Ok(root), Ok(path)) => path.starts_with(root),
+            _ => false,
+        }
+    }
+
+    // The site's own 404.html when it has one
+    fn not_found(&self, request: &Request) -> Response {
+        let page = self.root.join("404.html");
+        if page.is_file() && self.contains(&page) {
+            self.file(request, &page, 404)
+        } else {
+            Response::text(404, "Not Found")
+        }
+    }
+
+    fn file(&self, request: &Request, path: &Path, status: u16) -> Response {
+        let Ok(body) = fs::read(path) else {
+            return Response::text(500, "Internal Server Error");
+        };
+        let content_type = content_type(path);
+
+        // Browsers revalidate on every load, so a rebuilt site never looks stale
+        let mut response = Response::new(status).with_header("Cache-Control", "no-cache");
+        if status == 200 {
+            let tag = etag(path, body.len());
+            if request.get("If-None-Match") == Some(tag.as_str()) {
+                return Response::new(304).with_header("Cache-Control", "no-cache").with_header("ETag", tag);
+            }
+            response = response.with_header("ETag", tag);
+        }
+
+        if compressible(content_type) {
+            response = response.with_header("Vary", "Accept-Encoding");
+            if body.len() >= GZIP_MIN_SIZE && request.accepts_gzip() {
+                if let Some(compressed) = gzip(&body) {
+                    return response
+                        .with_header("Content-Encoding", "gzip")
+                        .with_body(content_type, compressed);
+                }
+            }
+        }
+        response.with_body(content_type, body)
+    }
+}
+
+// A static server running on a background thread of this process
+//
+// As a Process it has no pid and exits only if its thread panics, so the
+// supervisor can run it like a server command.
+#[derive(Debug)]
+pub struct Serving {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    accept: Option<JoinHandle<()>>,
+}
+
+impl Serving {
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Stop accepting connections; requests being answered still finish
+    pub fn stop(&mut self) {
+        let Some(accept) = self.accept.take() else {
+            return;
+        };
+        self.stop.store(true, Ordering::SeqCst);
+
+        // accept() only notices the flag once a connection comes in
+        let mut wake = self.addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(if wake.is_ipv4() { Ipv4Addr::LOCALHOST.into() } else { Ipv6Addr::LOCALHOST.into() });
+        }
+        if TcpStream::connect_timeout(&wake, Duration::from_secs(1)).is_ok() {
+            let _ = accept.join();
+        }
+    }
+
+    fn exit_status(accept: JoinHandle<()>) -> CommandOutput {
+        match accept.join() {
+            Ok(()) => CommandOutput::ok(""),
+            Err(_) => CommandOutput::failed(1),
+        }
+    }
+}
+
+impl Drop for Serving {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+impl Process for Serving {
+    fn id(&self) -> Option<u32> {
+        None
+    }
+
+    fn wait(&mut self) -> Result<CommandOutput> {
+        Ok(self.accept.take().map_or_else(|| CommandOutput::ok(""), Self::exit_status))
+    }
+
+    fn try_wait(&mut self) -> Result<Option<CommandOutput>> {
+        match self.accept.take() {
+            Some(accept) if accept.is_finished() => Ok(Some(Self::exit_status(accept))),
+            Some(accept) => {
+                self.accept = Some(accept);
+                Ok(None)
+            }
+            None => Ok(Some(CommandOutput::ok(""))),
+        }
+    }
+}
+
+// Changes whenever the file is rewritten, which every site build does
+fn etag(path: &Path, len: usize) -> String {
+    let modified = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |since| since.as_nanos());
+    format!("\"{:x}-{:x}\"", len, modified)
+}
+
+fn gzip(body: &[u8]) -> Option<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body).ok()?;
+    encoder.finish().ok()
+}
+
+// Decode %XX escapes; None for malformed escapes or invalid UTF-8
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
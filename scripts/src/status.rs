@@ -0,0 +1,180 @@
+// Release state for `doc-cli status`
+//
+// Everything here is read-only: tags come from git, deployed versions from
+// the deployment branch without checking it out, and the server check only
+// tries to connect to the configured port.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::context::Context;
+use crate::deploy::Deployer;
+use crate::exec::Cmd;
+use crate::VersionBumper;
+
+// A version listed in a versions.json file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VersionEntry {
+    pub version: String,
+    pub aliases: Vec<String>,
+}
+
+// Read the versions from either versions.json layout: mike's list of entries
+// on the deployment branch, or the `{"versions": [...]}` file in the project
+pub fn parse_versions_file(content: &str) -> Option<Vec<VersionEntry>> {
+    let document: Value = serde_json::from_str(content).ok()?;
+    let entries = match &document {
+        Value::Array(entries) => entries,
+        Value::Object(object) => object.get("versions")?.as_array()?,
+        _ => return None,
+    };
+
+    let strings = |value: Option<&Value>| -> Vec<String> {
+        value
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+
+    Some(
+        entries
+            .iter()
+            .filter_map(|entry| {
+                Some(VersionEntry {
+                    version: entry.get("version")?.as_str()?.to_string(),
+                    aliases: strings(entry.get("aliases")),
+                })
+            })
+            .collect(),
+    )
+}
+
+// Differences between the project's versions.json and the deployed one
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct VersionsDrift {
+    // False when the file is missing or unreadable on that side
+    pub local_found: bool,
+    pub deployed_found: bool,
+    // Versions, without the tag prefix, listed on only one side
+    pub only_local: Vec<String>,
+    pub only_deployed: Vec<String>,
+    // Targets of the `latest` alias on each side
+    pub local_latest: Option<String>,
+    pub deployed_latest: Option<String>,
+}
+
+impl VersionsDrift {
+    pub fn compare(local: Option<&[VersionEntry]>, deployed: Option<&[VersionEntry]>, tag_prefix: &str) -> Self {
+        let versions = |entries: Option<&[VersionEntry]>| -> BTreeSet<String> {
+            entries
+                .unwrap_or_default()
+                .iter()
+                .map(|entry| entry.version.strip_prefix(tag_prefix).unwrap_or(&entry.version).to_string())
+                .collect()
+        };
+        let (local_versions, deployed_versions) = (versions(local), versions(deployed));
+
+        Self {
+            local_found: local.is_some(),
+            deployed_found: deployed.is_some(),
+            only_local: local_versions.difference(&deployed_versions).cloned().collect(),
+            only_deployed: deployed_versions.difference(&local_versions).cloned().collect(),
+            local_latest: latest_alias(local.unwrap_or_default()),
+            deployed_latest: latest_alias(deployed.unwrap_or_default()),
+        }
+    }
+
+    pub fn in_sync(&self) -> bool {
+        self.local_found
+            && self.deployed_found
+            && self.only_local.is_empty()
+            && self.only_deployed.is_empty()
+            && self.local_latest == self.deployed_latest
+    }
+}
+
+// Version the `latest` alias points to
+fn latest_alias(entries: &[VersionEntry]) -> Option<String> {
+    entries
+        .iter()
+        .find(|entry| entry.aliases.iter().any(|alias| alias == "latest"))
+        .map(|entry| entry.version.clone())
+}
+
+// The `--output json` result of `status`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StatusReport {
+    // Latest tag as bump-version sees it, None without tags
+    pub latest_tag: Option<String>,
+    pub commits_since_tag: Option<u32>,
+    pub deploy_branch: String,
+    pub deployed_versions: Vec<String>,
+    pub latest_alias: Option<String>,
+    // Tags not on the deployment branch yet, oldest first
+    pub undeployed_tags: Vec<String>,
+    pub versions_json: VersionsDrift,
+    pub port: u16,
+    pub server_listening: bool,
+}
+
+impl StatusReport {
+    pub fn gather(ctx: &Context) -> Self {
+        let runner = ctx.runner.as_ref();
+        let root = ctx.project.root();
+        let git = || Cmd::new("git", root).capture();
+        let branch = ctx.config.gh_pages_branch.value.clone();
+        let prefix = &ctx.config.tag_prefix.value;
+
+        let version = VersionBumper::get_latest_tag(runner, root, prefix);
+        let latest_tag = (version != "0.0.0").then(|| ctx.config.tag_name(&version));
+
+        let commits_since_tag = latest_tag.as_ref().and_then(|tag| {
+            let count = git().args(["rev-list", "--count"]).arg(format!("{}..HEAD", tag));
+            runner
+                .run(&count)
+                .ok()
+                .filter(|output| output.success())
+                .and_then(|output| output.stdout.trim().parse().ok())
+        });
+
+        let deployed_versions = Deployer::list_deployed_versions(runner, root, &ctx.config);
+
+        let tags: Vec<String> = match runner.run(&git().args(["tag", "--sort=v:refname"])) {
+            Ok(output) if output.success() => output.stdout.lines().map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        let undeployed_tags = tags.into_iter().filter(|tag| !deployed_versions.contains(tag)).collect();
+
+        let deployed_file = match runner.run(&git().arg("show").arg(format!("{}:versions.json", branch))) {
+            Ok(output) if output.success() => parse_versions_file(&output.stdout),
+            _ => None,
+        };
+        let local_file = fs::read_to_string(ctx.project.versions_json())
+            .ok()
+            .and_then(|content| parse_versions_file(&content));
+        let versions_json = VersionsDrift::compare(local_file.as_deref(), deployed_file.as_deref(), prefix);
+
+        Self {
+            latest_tag,
+            commits_since_tag,
+            deploy_branch: branch,
+            deployed_versions,
+            latest_alias: versions_json.deployed_latest.clone(),
+            undeployed_tags,
+            versions_json,
+            port: ctx.config.port.value,
+            server_listening: port_listening(ctx.config.port.value),
+        }
+    }
+}
+
+// Whether something accepts connections on `port` on this machine
+pub fn port_listening(port: u16) -> bool {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&addr, Duration::from_millis(200)).is_ok()
+}
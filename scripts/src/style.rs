@@ -0,0 +1,118 @@
+// Terminal styling shared by every tool
+//
+// Messages are written with the color constants below embedded in them.
+// Whether the escapes reach the terminal is decided per stream when the
+// message is printed: they are kept only for a terminal (or with
+// --color=always) and removed for pipes, files, NO_COLOR and --color=never.
+
+use std::borrow::Cow;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use clap::ValueEnum;
+
+// ANSI color codes
+pub const GREEN: &str = "\x1b[0;32m";
+pub const YELLOW: &str = "\x1b[1;33m";
+pub const BLUE: &str = "\x1b[0;34m";
+pub const RED: &str = "\x1b[0;31m";
+pub const NC: &str = "\x1b[0m"; // No Color
+
+// When to color output, as given with --color
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    // Color terminals unless NO_COLOR is set or TERM is dumb
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+// One of the two streams the tools write messages to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// Until init runs, behave like --color=never so nothing leaks into pipes
+static COLOR_STDOUT: AtomicBool = AtomicBool::new(false);
+static COLOR_STDERR: AtomicBool = AtomicBool::new(false);
+static UNICODE: AtomicBool = AtomicBool::new(false);
+
+// Decide once per process whether each stream gets color and emoji
+pub fn init(choice: ColorChoice) {
+    let auto = || {
+        let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+        !no_color && env::var("TERM").as_deref() != Ok("dumb")
+    };
+
+    let (stdout, stderr) = match choice {
+        ColorChoice::Always => (true, true),
+        ColorChoice::Never => (false, false),
+        ColorChoice::Auto => (auto() && io::stdout().is_terminal(), auto() && io::stderr().is_terminal()),
+    };
+
+    COLOR_STDOUT.store(stdout, Ordering::Relaxed);
+    COLOR_STDERR.store(stderr, Ordering::Relaxed);
+    UNICODE.store(locale_is_utf8(), Ordering::Relaxed);
+}
+
+// Whether escapes written to `stream` are kept
+pub fn color_enabled(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => COLOR_STDOUT.load(Ordering::Relaxed),
+        Stream::Stderr => COLOR_STDERR.load(Ordering::Relaxed),
+    }
+}
+
+// Prepare a message for `stream`, removing its color codes if needed
+pub fn render(text: &str, stream: Stream) -> Cow<'_, str> {
+    if color_enabled(stream) || !text.contains('\x1b') {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(strip_ansi(text))
+    }
+}
+
+// `fancy` on UTF-8 terminals, the ASCII `plain` fallback elsewhere
+pub fn icon(fancy: &'static str, plain: &'static str) -> &'static str {
+    if UNICODE.load(Ordering::Relaxed) {
+        fancy
+    } else {
+        plain
+    }
+}
+
+// Remove terminal color codes, e.g. for log files and pipes
+pub fn strip_ansi(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip up to and including the final byte of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+// The first of LC_ALL, LC_CTYPE and LANG that is set names a UTF-8 locale
+fn locale_is_utf8() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
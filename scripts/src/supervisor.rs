@@ -0,0 +1,361 @@
+// Runs the documentation server as a managed child process, or the built-in
+// static server on a thread
+//
+// A server command's output goes to a log file, which the supervisor forwards
+// line by line. It polls the site over HTTP to tell when it is ready, and
+// when the server crashes (say after a YAML mistake in mkdocs.yml) starts it
+// again, waiting twice as long after every crash until the site is back.
+
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::cleanup::ChildGuard;
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandOutput, CommandRunner, FinishedProcess, Process};
+use crate::static_server::StaticServer;
+use crate::{debug, info, say, warn};
+
+// Crashes before the site was first ready after which startup gives up
+pub const START_ATTEMPTS: u32 = 3;
+
+// Delay before the first restart; doubled after each crash up to MAX_BACKOFF
+pub const BACKOFF: Duration = Duration::from_secs(1);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+// How long the first build may take before startup stops waiting for it
+pub const READY_TIMEOUT: Duration = Duration::from_secs(120);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+// How waiting for the first start ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Start {
+    // The site answers HTTP requests
+    Ready,
+    // The server already exited without an error, or never ran in a dry run
+    Exited,
+    // The server runs but did not answer within the ready timeout
+    NotReady,
+}
+
+// The server the supervisor keeps running
+#[derive(Debug)]
+pub enum Service {
+    // A command such as `mkdocs serve`, run through the command runner
+    Command(Cmd),
+    // The built-in static server, on a thread of this process
+    Static { server: StaticServer, bind: String, port: u16 },
+}
+
+impl From<Cmd> for Service {
+    fn from(cmd: Cmd) -> Self {
+        Self::Command(cmd)
+    }
+}
+
+// What happened while polling the server
+enum Poll {
+    Ready,
+    Exited(CommandOutput),
+    TimedOut,
+}
+
+#[derive(Debug)]
+pub struct Supervisor {
+    runner: Arc<dyn CommandRunner>,
+    service: Service,
+    // Host and port the readiness probe connects to
+    probe: (String, u16),
+    // Where the site is, announced once it answers late
+    url: String,
+    // Whether the site has answered since the server first started
+    ready: bool,
+    log: Option<PathBuf>,
+    backoff: Duration,
+    max_backoff: Duration,
+    ready_timeout: Duration,
+    // Only report starting the static server; commands go through the runner
+    dry_run: bool,
+    process: Option<Box<dyn Process>>,
+    // Stops the current server if doc-cli ends before it does
+    guard: Option<ChildGuard>,
+    // Bytes of the log already forwarded, and the last unfinished line
+    forwarded: u64,
+    partial: String,
+}
+
+impl Supervisor {
+    // Supervise `service`, probing http://host:port/ for readiness; a
+    // command's output goes to `log`, or stays on the terminal without one
+    pub fn new(
+        runner: Arc<dyn CommandRunner>,
+        service: impl Into<Service>,
+        probe: (String, u16),
+        log: Option<PathBuf>,
+    ) -> Self {
+        let service = match (service.into(), &log) {
+            (Service::Command(cmd), Some(path)) => Service::Command(cmd.log_to(path)),
+            (service, _) => service,
+        };
+
+        Self {
+            runner,
+            service,
+            url: format!("http://{}:{}/", probe.0, probe.1),
+            ready: false,
+            probe,
+            log,
+            backoff: BACKOFF,
+            max_backoff: MAX_BACKOFF,
+            ready_timeout: READY_TIMEOUT,
+            dry_run: false,
+            process: None,
+            guard: None,
+            forwarded: 0,
+            partial: String::new(),
+        }
+    }
+
+    // Restart after `first`, doubling the delay after each crash up to `max`
+    pub fn backoff(mut self, first: Duration, max: Duration) -> Self {
+        self.backoff = first;
+        self.max_backoff = max;
+        self
+    }
+
+    pub fn ready_timeout(mut self, timeout: Duration) -> Self {
+        self.ready_timeout = timeout;
+        self
+    }
+
+    // The site's address as users open it, e.g. with a version path
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = url.into();
+        self
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    pub fn log(&self) -> Option<&Path> {
+        self.log.as_deref()
+    }
+
+    // Pid of the server command currently running
+    pub fn pid(&self) -> Option<u32> {
+        self.process.as_ref().and_then(|process| process.id())
+    }
+
+    // Start the server and wait until the site answers, restarting it if it
+    // crashes first; gives up after START_ATTEMPTS crashes
+    pub fn start(&mut self) -> Result<Start> {
+        if let Some(path) = &self.log {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| DocToolsError::io(format!("Failed to create {}", dir.display()), e))?;
+            }
+            File::create(path).map_err(|e| DocToolsError::io(format!("Failed to create {}", path.display()), e))?;
+        }
+
+        let mut delay = self.backoff;
+        let mut attempt = 1;
+        loop {
+            self.spawn()?;
+            match self.poll_until_ready(self.ready_timeout)? {
+                Poll::Ready => {
+                    self.ready = true;
+                    return Ok(Start::Ready);
+                }
+                Poll::TimedOut => return Ok(Start::NotReady),
+                Poll::Exited(output) if !crashed(&output) => return Ok(Start::Exited),
+                Poll::Exited(output) if attempt == START_ATTEMPTS => {
+                    return Err(DocToolsError::Python(format!(
+                        "Documentation server {} {} times before the site was ready{}",
+                        describe(&output),
+                        START_ATTEMPTS,
+                        self.see_log()
+                    )));
+                }
+                Poll::Exited(output) => {
+                    warn!(
+                        "Documentation server {} before the site was ready; restarting in {:.1}s",
+                        describe(&output),
+                        delay.as_secs_f32()
+                    );
+                    thread::sleep(delay);
+                    delay = (delay * 2).min(self.max_backoff);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    // Keep the server running until it exits by itself, restarting it with
+    // backoff whenever it crashes
+    pub fn supervise(mut self) -> Result<()> {
+        let mut delay = self.backoff;
+        let mut restarted = false;
+
+        loop {
+            self.forward_log();
+
+            if let Some(output) = self.try_wait()? {
+                if !crashed(&output) {
+                    debug!("Documentation server stopped");
+                    return Ok(());
+                }
+
+                warn!(
+                    "Documentation server {}; restarting in {:.1}s{}",
+                    describe(&output),
+                    delay.as_secs_f32(),
+                    self.see_log()
+                );
+                thread::sleep(delay);
+                delay = (delay * 2).min(self.max_backoff);
+                self.spawn()?;
+                restarted = true;
+            } else if (restarted || !self.ready) && responds(&self.probe) {
+                // Startup stops waiting after the ready timeout, so a slow
+                // first build gets its URL here
+                if self.ready {
+                    info!("Documentation server is back up.");
+                } else {
+                    info!("Documentation server is up; your documentation is now available at {}", self.url);
+                }
+                self.ready = true;
+                delay = self.backoff;
+                restarted = false;
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn spawn(&mut self) -> Result<()> {
+        // Let go of the previous server first, so its port is free again
+        self.process = None;
+
+        let process: Box<dyn Process> = match &self.service {
+            Service::Command(cmd) => {
+                let process = self.runner.spawn(cmd)?;
+                self.guard = process
+                    .id()
+                    .map(|pid| ChildGuard::new(Arc::clone(&self.runner), &cmd.cwd, pid));
+                process
+            }
+            Service::Static { server, bind, port } if self.dry_run => {
+                say!("[dry-run] skip serving {} on {}:{}", server.root().display(), bind, port);
+                Box::new(FinishedProcess::new(None, CommandOutput::skipped()))
+            }
+            // Stopped when the supervisor drops it, together with doc-cli otherwise
+            Service::Static { server, bind, port } => Box::new(server.spawn(bind, *port)?),
+        };
+        self.process = Some(process);
+        Ok(())
+    }
+
+    // Exit status of the current server once it has exited
+    fn try_wait(&mut self) -> Result<Option<CommandOutput>> {
+        let Some(process) = self.process.as_mut() else {
+            return Ok(None);
+        };
+
+        let exited = process.try_wait()?;
+        if exited.is_some() {
+            // The pid is gone and may be reused, so never signal it again
+            if let Some(guard) = self.guard.take() {
+                guard.disarm();
+            }
+            self.forward_log();
+        }
+        Ok(exited)
+    }
+
+    fn poll_until_ready(&mut self, timeout: Duration) -> Result<Poll> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            self.forward_log();
+
+            // Checked first so a stale server on the port cannot look like ours
+            if let Some(output) = self.try_wait()? {
+                return Ok(Poll::Exited(output));
+            }
+            if responds(&self.probe) {
+                return Ok(Poll::Ready);
+            }
+            if Instant::now() >= deadline {
+                return Ok(Poll::TimedOut);
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    // Print whole lines the server wrote to the log since the last call
+    fn forward_log(&mut self) {
+        let Some(path) = &self.log else {
+            return;
+        };
+        let Ok(mut file) = File::open(path) else {
+            return;
+        };
+
+        let mut new = Vec::new();
+        if file.seek(SeekFrom::Start(self.forwarded)).is_err() || file.read_to_end(&mut new).is_err() {
+            return;
+        }
+        self.forwarded += new.len() as u64;
+        self.partial.push_str(&String::from_utf8_lossy(&new));
+
+        while let Some(end) = self.partial.find('\n') {
+            let line: String = self.partial.drain(..=end).collect();
+            info!("{}", line.trim_end());
+        }
+    }
+
+    fn see_log(&self) -> String {
+        match &self.log {
+            Some(path) => format!(" (see {})", path.display()),
+            None => String::new(),
+        }
+    }
+}
+
+// Whether a server answers HTTP requests on host:port
+pub fn responds((host, port): &(String, u16)) -> bool {
+    let Some(addr) = (host.as_str(), *port).to_socket_addrs().ok().and_then(|mut addrs| addrs.next()) else {
+        return false;
+    };
+    let Ok(mut stream) = TcpStream::connect_timeout(&addr, POLL_INTERVAL) else {
+        return false;
+    };
+
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+    let request = format!("HEAD / HTTP/1.0\r\nHost: {}:{}\r\n\r\n", host, port);
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut status = [0; 5];
+    stream.read_exact(&mut status).is_ok() && &status == b"HTTP/"
+}
+
+// Exiting with an error is a crash. A clean exit or a signal means someone
+// stopped the server on purpose, including doc-cli's own exit with 128 plus
+// SIGINT or SIGTERM
+fn crashed(output: &CommandOutput) -> bool {
+    matches!(output.code, Some(code) if code != 0 && code != 130 && code != 143)
+}
+
+fn describe(output: &CommandOutput) -> String {
+    match output.code {
+        Some(code) => format!("exited with status {}", code),
+        None => "was terminated by a signal".to_string(),
+    }
+}
@@ -0,0 +1,531 @@
+// Full-screen interactive menu for doc-cli
+//
+// The menu only collects what to run: picking an entry opens a small form
+// for that command's options, and the chosen command is returned to the
+// caller, which leaves the full-screen view before running it so the
+// command's output stays in the terminal scrollback.
+
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::bump_version::{DeployTarget, VersionBumper};
+use crate::cleanup;
+use crate::cli::{BumpVersionArgs, Command, ConfigAction, DeployArgs, StartupArgs};
+use crate::context::Context;
+use crate::deploy::Deployer;
+use crate::error::{DocToolsError, Result};
+use crate::plugins::Plugin;
+use crate::status::port_listening;
+use crate::style::{self, Stream};
+
+// How often the dev server check in the status panel is repeated
+const SERVER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// What the user picked in the menu
+#[derive(Debug, Clone)]
+pub enum MenuOutcome {
+    Run(Command),
+    Help,
+    Quit,
+}
+
+// Facts about the project shown next to the menu
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    // Latest version tag, None if the repository has no tags yet
+    pub current_tag: Option<String>,
+    // Latest version without the tag prefix, 0.0.0 without tags
+    pub current_version: String,
+    pub deployed_versions: Vec<String>,
+    pub server_running: bool,
+    pub port: u16,
+}
+
+impl Status {
+    // Query git and the server port; nothing is printed
+    pub fn gather(ctx: &Context) -> Self {
+        let runner = ctx.runner.as_ref();
+        let root = ctx.project.root();
+        let current_version = VersionBumper::get_latest_tag(runner, root, &ctx.config.tag_prefix.value);
+        let current_tag = (current_version != "0.0.0").then(|| ctx.config.tag_name(&current_version));
+
+        Self {
+            current_tag,
+            current_version,
+            deployed_versions: Deployer::list_deployed_versions(runner, root, &ctx.config),
+            server_running: port_listening(ctx.config.port.value),
+            port: ctx.config.port.value,
+        }
+    }
+}
+
+// Entries of the main menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Startup,
+    BumpVersion,
+    Deploy,
+    Config,
+    Help,
+    Quit,
+    // A doc-cli-<name> plugin, named by the menu item
+    Plugin,
+}
+
+const ACTIONS: [(Action, &str, &str); 6] = [
+    (Action::Startup, "startup", "Start the development environment"),
+    (Action::BumpVersion, "bump-version", "Bump the documentation version"),
+    (Action::Deploy, "deploy", "Deploy all versions to GitHub Pages"),
+    (Action::Config, "config", "Show the effective configuration"),
+    (Action::Help, "help", "Show command help information"),
+    (Action::Quit, "quit", "Leave doc-cli"),
+];
+
+// An entry of the main menu
+#[derive(Debug, Clone)]
+struct MenuItem {
+    action: Action,
+    name: String,
+    description: String,
+}
+
+// The built-in commands with the plugins inserted before config, help and quit
+fn menu_items(plugins: &[Plugin]) -> Vec<MenuItem> {
+    let item = |(action, name, description): &(Action, &str, &str)| MenuItem {
+        action: *action,
+        name: name.to_string(),
+        description: description.to_string(),
+    };
+    let plugin_items = plugins.iter().map(|plugin| MenuItem {
+        action: Action::Plugin,
+        name: plugin.name.clone(),
+        description: format!("Plugin {}", plugin.path.display()),
+    });
+
+    ACTIONS[..3]
+        .iter()
+        .map(item)
+        .chain(plugin_items)
+        .chain(ACTIONS[3..].iter().map(item))
+        .collect()
+}
+
+// One input of a command form
+#[derive(Debug, Clone)]
+enum Field {
+    Choice {
+        label: &'static str,
+        options: Vec<String>,
+        selected: usize,
+    },
+    Text {
+        label: &'static str,
+        value: String,
+    },
+}
+
+// Options for one command, filled in before it runs
+#[derive(Debug, Clone)]
+struct Form {
+    action: Action,
+    fields: Vec<Field>,
+    // Index of the focused field; fields.len() is the Run button
+    focus: usize,
+    error: Option<String>,
+}
+
+impl Form {
+    // Form for a menu action, None for actions without options
+    fn for_action(action: Action, status: &Status) -> Option<Self> {
+        let (major, minor, patch) = VersionBumper::parse_version(&status.current_version);
+
+        let fields = match action {
+            Action::Startup => vec![
+                Field::Choice {
+                    label: "Serve",
+                    options: vec![
+                        "live preview with mkdocs serve".to_string(),
+                        "draft build of an undeployed version".to_string(),
+                    ],
+                    selected: 0,
+                },
+                Field::Text {
+                    label: "Draft version",
+                    value: format!("{}.{}.{}", major, minor, patch + 1),
+                },
+            ],
+            Action::BumpVersion => vec![
+                Field::Choice {
+                    label: "Bump",
+                    options: vec![
+                        format!("major -> {}.0.0", major + 1),
+                        format!("minor -> {}.{}.0", major, minor + 1),
+                        format!("patch -> {}.{}.{}", major, minor, patch + 1),
+                    ],
+                    selected: 2,
+                },
+                Field::Choice {
+                    label: "Deploy",
+                    options: vec![
+                        "no, deploy later".to_string(),
+                        "yes, as a regular version".to_string(),
+                        "yes, and set as 'latest'".to_string(),
+                    ],
+                    selected: 0,
+                },
+            ],
+            Action::Deploy => vec![Field::Choice {
+                label: "Mode",
+                options: vec![
+                    "smart: only versions missing from the deploy branch".to_string(),
+                    "force: redeploy every version".to_string(),
+                ],
+                selected: 0,
+            }],
+            Action::Config | Action::Help | Action::Quit | Action::Plugin => return None,
+        };
+
+        Some(Self {
+            action,
+            fields,
+            focus: 0,
+            error: None,
+        })
+    }
+
+    fn choice(&self, index: usize) -> usize {
+        match &self.fields[index] {
+            Field::Choice { selected, .. } => *selected,
+            Field::Text { .. } => 0,
+        }
+    }
+
+    fn text(&self, index: usize) -> &str {
+        match &self.fields[index] {
+            Field::Text { value, .. } => value.trim(),
+            Field::Choice { .. } => "",
+        }
+    }
+
+    // The command this form describes, or why it cannot run yet
+    fn command(&self) -> std::result::Result<Command, String> {
+        match self.action {
+            Action::Startup => {
+                let draft_version = match self.choice(0) {
+                    0 => None,
+                    _ if self.text(1).is_empty() => return Err("Enter the draft version to build.".to_string()),
+                    _ => Some(self.text(1).to_string()),
+                };
+                Ok(Command::Startup(StartupArgs { draft_version, ..StartupArgs::default() }))
+            }
+            Action::BumpVersion => {
+                let bump = self.choice(0);
+                let deploy = match self.choice(1) {
+                    0 => DeployTarget::None,
+                    1 => DeployTarget::Version,
+                    _ => DeployTarget::Latest,
+                };
+                // Submitting the form is the confirmation
+                Ok(Command::BumpVersion(BumpVersionArgs {
+                    major: bump == 0,
+                    minor: bump == 1,
+                    patch: bump == 2,
+                    yes: true,
+                    deploy: Some(deploy),
+                    ..BumpVersionArgs::default()
+                }))
+            }
+            Action::Deploy => Ok(Command::Deploy(DeployArgs {
+                force: self.choice(0) == 1,
+                non_interactive: true,
+            })),
+            Action::Config | Action::Help | Action::Quit | Action::Plugin => Err("Nothing to run.".to_string()),
+        }
+    }
+
+    // Handle a key; returns the command once the form is submitted
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Command> {
+        let last = self.fields.len();
+
+        match key.code {
+            KeyCode::Up | KeyCode::BackTab => self.focus = self.focus.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => self.focus = (self.focus + 1).min(last),
+            KeyCode::Left | KeyCode::Right => {
+                if let Some(Field::Choice { options, selected, .. }) = self.fields.get_mut(self.focus) {
+                    *selected = if key.code == KeyCode::Right {
+                        (*selected + 1) % options.len()
+                    } else {
+                        (*selected + options.len() - 1) % options.len()
+                    };
+                }
+            }
+            KeyCode::Backspace => {
+                if let Some(Field::Text { value, .. }) = self.fields.get_mut(self.focus) {
+                    value.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(Field::Text { value, .. }) = self.fields.get_mut(self.focus) {
+                    value.push(c);
+                }
+            }
+            KeyCode::Enter => match self.command() {
+                Ok(command) => return Some(command),
+                Err(message) => self.error = Some(message),
+            },
+            _ => {}
+        }
+        None
+    }
+}
+
+struct Menu<'a> {
+    ctx: &'a Context,
+    status: Status,
+    items: Vec<MenuItem>,
+    list: ListState,
+    form: Option<Form>,
+    last_poll: Instant,
+}
+
+// Show the menu until the user picks something
+pub fn run(ctx: &Context, plugins: &[Plugin]) -> Result<MenuOutcome> {
+    // Gather the status before taking over the screen, in case it prints
+    let mut menu = Menu {
+        ctx,
+        status: Status::gather(ctx),
+        items: menu_items(plugins),
+        list: ListState::default().with_selected(Some(0)),
+        form: None,
+        last_poll: Instant::now(),
+    };
+
+    let mut terminal = ratatui::try_init().map_err(|e| DocToolsError::io("Failed to start the terminal menu", e))?;
+    // Leave raw mode and the alternate screen even when killed
+    let restore = cleanup::register(ratatui::restore);
+    let outcome = menu.event_loop(&mut terminal);
+    restore.run();
+    outcome
+}
+
+impl Menu<'_> {
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<MenuOutcome> {
+        let io_error = |e| DocToolsError::io("Terminal menu failed", e);
+
+        loop {
+            terminal.draw(|frame| self.draw(frame)).map_err(io_error)?;
+
+            // Wake up now and then to keep the server status current
+            if !event::poll(Duration::from_millis(250)).map_err(io_error)? {
+                if self.last_poll.elapsed() >= SERVER_POLL_INTERVAL {
+                    self.status.server_running = port_listening(self.status.port);
+                    self.last_poll = Instant::now();
+                }
+                continue;
+            }
+
+            let Event::Key(key) = event::read().map_err(io_error)? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                return Ok(MenuOutcome::Quit);
+            }
+
+            if let Some(form) = &mut self.form {
+                if key.code == KeyCode::Esc {
+                    self.form = None;
+                } else if let Some(command) = form.handle_key(key) {
+                    return Ok(MenuOutcome::Run(command));
+                }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Up | KeyCode::Char('k') => self.list.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.list.select_next(),
+                KeyCode::Char('r') => {
+                    self.status = Status::gather(self.ctx);
+                    terminal.clear().map_err(io_error)?;
+                }
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(MenuOutcome::Quit),
+                KeyCode::Char(c @ '1'..='9') if (c as usize - '1' as usize) < self.items.len() => {
+                    let index = c as usize - '1' as usize;
+                    self.list.select(Some(index));
+                    if let Some(outcome) = self.choose(index) {
+                        return Ok(outcome);
+                    }
+                }
+                KeyCode::Enter => {
+                    let index = self.list.selected().unwrap_or(0).min(self.items.len() - 1);
+                    if let Some(outcome) = self.choose(index) {
+                        return Ok(outcome);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Open the item's form, or finish the menu for items without one
+    fn choose(&mut self, index: usize) -> Option<MenuOutcome> {
+        let item = &self.items[index];
+
+        match item.action {
+            Action::Plugin => Some(MenuOutcome::Run(Command::External(vec![item.name.clone()]))),
+            Action::Config => Some(MenuOutcome::Run(Command::Config {
+                action: ConfigAction::Show,
+            })),
+            Action::Help => Some(MenuOutcome::Help),
+            Action::Quit => Some(MenuOutcome::Quit),
+            Action::Startup | Action::BumpVersion | Action::Deploy => {
+                self.form = Form::for_action(item.action, &self.status);
+                None
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [title, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(5),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+
+        let heading = format!("{} MkDocs Documentation CLI Tool", style::icon("📚", "[docs]"));
+        frame.render_widget(Paragraph::new(heading).style(Style::new().add_modifier(Modifier::BOLD)), title);
+
+        match &self.form {
+            Some(form) => draw_form(frame, left, form),
+            None => self.draw_list(frame, left),
+        }
+        draw_status(frame, right, &self.status, self.ctx);
+
+        let hints = match (&self.form, style::icon("↑↓", "up/down")) {
+            (Some(_), arrows) => format!(
+                " {} field   {} change   Enter run   Esc back",
+                arrows,
+                style::icon("←→", "left/right")
+            ),
+            (None, arrows) => format!(
+                " {} move   Enter select   1-{} jump   r refresh   q quit",
+                arrows,
+                self.items.len().min(9)
+            ),
+        };
+        frame.render_widget(Paragraph::new(hints).style(Style::new().add_modifier(Modifier::DIM)), footer);
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, MenuItem { name, description, .. })| {
+                ListItem::new(vec![
+                    Line::from(Span::styled(
+                        format!("{}. {}", index + 1, name),
+                        Style::new().add_modifier(Modifier::BOLD),
+                    )),
+                    Line::from(format!("   {}", description)),
+                ])
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::bordered().title(" Commands "))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(style::icon("▶ ", "> "));
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+}
+
+fn draw_form(frame: &mut Frame, area: Rect, form: &Form) {
+    let name = ACTIONS
+        .iter()
+        .find(|(action, _, _)| *action == form.action)
+        .map_or("", |(_, name, _)| name);
+
+    let focused = |index: usize| {
+        if form.focus == index {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new()
+        }
+    };
+
+    let mut lines = Vec::new();
+    for (index, field) in form.fields.iter().enumerate() {
+        let line = match field {
+            Field::Choice {
+                label,
+                options,
+                selected,
+            } => Line::from(vec![
+                Span::raw(format!("{:<14}", label)),
+                Span::styled(format!("< {} >", options[*selected]), focused(index)),
+            ]),
+            Field::Text { label, value } => Line::from(vec![
+                Span::raw(format!("{:<14}", label)),
+                Span::styled(format!("[{}_]", value), focused(index)),
+            ]),
+        };
+        lines.push(line);
+        lines.push(Line::default());
+    }
+    lines.push(Line::from(Span::styled("[ Run ]", focused(form.fields.len()))));
+
+    if let Some(error) = &form.error {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(error.clone(), accent(Color::Red))));
+    }
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::bordered().title(format!(" {} ", name)))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_status(frame: &mut Frame, area: Rect, status: &Status, ctx: &Context) {
+    let server = if status.server_running {
+        Span::styled(format!("running on port {}", status.port), accent(Color::Green))
+    } else {
+        Span::styled(format!("not running (port {})", status.port), accent(Color::Yellow))
+    };
+
+    let mut lines = vec![
+        Line::from(format!("Project     {}", ctx.project.root().display())),
+        Line::from(format!("Latest tag  {}", status.current_tag.as_deref().unwrap_or("none"))),
+        Line::from(vec![Span::raw("Dev server  "), server]),
+        Line::default(),
+        Line::from(format!(
+            "Deployed to {} ({})",
+            ctx.config.gh_pages_branch.value,
+            status.deployed_versions.len()
+        )),
+    ];
+    lines.extend(status.deployed_versions.iter().rev().map(|version| Line::from(format!("  {}", version))));
+
+    let paragraph = Paragraph::new(lines).block(Block::bordered().title(" Status "));
+    frame.render_widget(paragraph, area);
+}
+
+// Bold text, colored too when color output is enabled
+fn accent(color: Color) -> Style {
+    let style = Style::new().add_modifier(Modifier::BOLD);
+    if style::color_enabled(Stream::Stdout) {
+        style.fg(color)
+    } else {
+        style
+    }
+}
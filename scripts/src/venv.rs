@@ -0,0 +1,201 @@
+// The project's .venv, which startup creates and every python tool runs from
+//
+// Installing into a venv keeps pip, mkdocs and mike on one interpreter, no
+// matter which python or python3 comes first on PATH.
+
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, Source};
+use crate::error::{DocToolsError, Result};
+use crate::exec::{Cmd, CommandRunner};
+use crate::fingerprint::Fnv1a;
+use crate::project::Project;
+use crate::{debug, info};
+
+// Directory of the venv, relative to the project root
+pub const VENV_DIR: &str = ".venv";
+
+// Written into the venv after both pip installs succeeded
+pub const INSTALL_FINGERPRINT_FILE: &str = ".doc-tools-requirements";
+
+// Oldest Python the site is built with, as in .github/workflows/github_pages.yml
+pub const MIN_PYTHON: (u32, u32) = (3, 10);
+
+// Prints the interpreter's version as MAJOR.MINOR.PATCH
+const VERSION_SCRIPT: &str = "import sys; print('%d.%d.%d' % sys.version_info[:3])";
+
+// A Python version such as 3.10.12
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PythonVersion(pub u32, pub u32, pub u32);
+
+impl PythonVersion {
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        let text = text.strip_prefix("Python ").unwrap_or(text);
+        let mut parts = text.split('.').map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()
+        });
+
+        Some(Self(parts.next()??, parts.next()??, parts.next().flatten().unwrap_or(0)))
+    }
+
+    pub fn supported(self) -> bool {
+        (self.0, self.1) >= MIN_PYTHON
+    }
+}
+
+impl std::fmt::Display for PythonVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+// Version reported by `program`, None if it cannot be run
+pub fn python_version(runner: &dyn CommandRunner, cwd: &Path, program: &str) -> Option<PythonVersion> {
+    let query = Cmd::new(program, cwd).args(["-c", VERSION_SCRIPT]).capture();
+    match runner.run(&query) {
+        Ok(output) if output.success() => PythonVersion::parse(&output.stdout),
+        _ => None,
+    }
+}
+
+// Interpreter to create the venv with: python.executable when it was
+// configured, otherwise the first of python3 and python that is new enough
+pub fn find_interpreter(runner: &dyn CommandRunner, cwd: &Path, config: &Config) -> Result<(String, PythonVersion)> {
+    let candidates = match config.python.source {
+        Source::Default => vec!["python3".to_string(), "python".to_string()],
+        _ => vec![config.python.value.clone()],
+    };
+
+    let mut found = Vec::new();
+    for program in &candidates {
+        match python_version(runner, cwd, program) {
+            Some(version) if version.supported() => return Ok((program.clone(), version)),
+            Some(version) => found.push(format!("{} is {}", program, version)),
+            None => found.push(format!("{} not found", program)),
+        }
+    }
+
+    Err(DocToolsError::Python(format!(
+        "Python {}.{} or newer is required ({}); install it or set python.executable in doc-tools.toml",
+        MIN_PYTHON.0,
+        MIN_PYTHON.1,
+        found.join(", ")
+    )))
+}
+
+// Program to run for a tool installed with pip: the venv's copy when there
+// is a venv, otherwise whatever is on PATH
+pub fn program(project: &Project, name: &str) -> String {
+    let venv = Venv::at(project);
+    if venv.exists() {
+        venv.bin(name).to_string_lossy().into_owned()
+    } else {
+        name.to_string()
+    }
+}
+
+// The project's virtualenv
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Venv {
+    dir: PathBuf,
+}
+
+impl Venv {
+    pub fn at(project: &Project) -> Self {
+        Self {
+            dir: project.root().join(VENV_DIR),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    // Path of an executable installed in the venv, e.g. bin("mike")
+    pub fn bin(&self, name: &str) -> PathBuf {
+        if cfg!(windows) {
+            self.dir.join("Scripts").join(format!("{}.exe", name))
+        } else {
+            self.dir.join("bin").join(name)
+        }
+    }
+
+    pub fn python(&self) -> PathBuf {
+        self.bin("python")
+    }
+
+    pub fn exists(&self) -> bool {
+        self.python().is_file()
+    }
+
+    // Hash of requirements.txt, setup.py and the venv's interpreter path,
+    // everything the two pip installs depend on
+    pub fn install_fingerprint(&self, project: &Project) -> Result<String> {
+        let mut hasher = Fnv1a::new();
+        hasher.write(self.python().to_string_lossy().as_bytes());
+
+        for file in [project.requirements_txt(), project.setup_py()] {
+            hasher.write_u8(0);
+            if file.is_file() {
+                let contents = fs::read(&file).map_err(|e| DocToolsError::io(format!("Failed to read {}", file.display()), e))?;
+                hasher.write(&contents);
+            }
+        }
+        Ok(hasher.hex())
+    }
+
+    // Fingerprint recorded by the last successful install, if any
+    pub fn installed_fingerprint(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join(INSTALL_FINGERPRINT_FILE))
+            .ok()
+            .map(|content| content.trim().to_string())
+    }
+
+    pub fn record_install(&self, fingerprint: &str) -> Result<()> {
+        let path = self.dir.join(INSTALL_FINGERPRINT_FILE);
+        fs::write(&path, format!("{}\n", fingerprint))
+            .map_err(|e| DocToolsError::io(format!("Failed to write {}", path.display()), e))
+    }
+
+    // Reuse the venv if its Python is new enough, otherwise create it;
+    // `recreate` starts from an empty venv either way
+    pub fn ensure(&self, runner: &dyn CommandRunner, project_root: &Path, config: &Config, recreate: bool) -> Result<()> {
+        if self.exists() && !recreate {
+            let python = self.python().to_string_lossy().into_owned();
+            return match python_version(runner, project_root, &python) {
+                Some(version) if version.supported() => {
+                    debug!("Using {} (Python {})", self.dir.display(), version);
+                    Ok(())
+                }
+                Some(version) => Err(DocToolsError::Python(format!(
+                    "{} uses Python {}, but {}.{} or newer is required; rerun with --recreate-venv",
+                    VENV_DIR, version, MIN_PYTHON.0, MIN_PYTHON.1
+                ))),
+                None => Err(DocToolsError::Python(format!(
+                    "{} is broken; rerun with --recreate-venv",
+                    VENV_DIR
+                ))),
+            };
+        }
+
+        let (interpreter, version) = find_interpreter(runner, project_root, config)?;
+        let action = if recreate { "Recreating" } else { "Creating" };
+        info!("{} {} with {} (Python {})...", action, VENV_DIR, interpreter, version);
+
+        // --clear empties an existing venv before setting it up again
+        let mut create = Cmd::new(&interpreter, project_root).args(["-m", "venv"]);
+        if recreate {
+            create = create.arg("--clear");
+        }
+        let create = create.arg(self.dir.to_string_lossy()).mutating();
+
+        if !runner.run(&create)?.success() {
+            return Err(DocToolsError::Python(format!("Failed to create {}.", VENV_DIR)));
+        }
+        Ok(())
+    }
+}
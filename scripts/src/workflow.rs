@@ -0,0 +1,500 @@
+// Declarative release workflows for `doc-cli run`
+//
+// A workflow file lists named steps that run in order on top of the same
+// operations as the subcommands:
+//
+//   [vars]
+//   prerelease = false
+//
+//   [[step]]
+//   name = "Tag the release"
+//   run = "bump-version"
+//   bump = "minor"
+//
+//   [[step]]
+//   name = "Deploy"
+//   run = "deploy"
+//   if = "!prerelease"
+//
+// Strings in a step may refer to variables as {name}. The first failing step
+// stops the workflow unless it sets `continue_on_error = true`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::bump_version::{BumpOptions, BumpType, DeployTarget};
+use crate::context::Context;
+use crate::doctor::{Doctor, Outcome};
+use crate::error::{DocToolsError, Result};
+use crate::exec::Cmd;
+use crate::project::Project;
+use crate::style::{BLUE, NC};
+use crate::{info, Deployer, Startup, VersionBumper};
+
+// Workflow run by `doc-cli run` without an argument
+pub const DEFAULT_WORKFLOW: &str = "release.toml";
+
+// Operation a step runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StepKind {
+    // pip install the requirements, as startup does
+    Install,
+    // mkdocs build into site/
+    Build,
+    // Every doctor check; fails if any check fails
+    Doctor,
+    // Tag a new version; needs `bump` or `set`
+    BumpVersion,
+    // Deploy every tagged version that is not deployed yet
+    Deploy,
+    // Point `latest` at `version` and push the deployment branch
+    SetLatest,
+    // Any other program, given as `command = ["program", "arg", ...]`
+    Command,
+}
+
+impl StepKind {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Install => "install",
+            Self::Build => "build",
+            Self::Doctor => "doctor",
+            Self::BumpVersion => "bump-version",
+            Self::Deploy => "deploy",
+            Self::SetLatest => "set-latest",
+            Self::Command => "command",
+        }
+    }
+
+    // Step options that mean something for this kind of step
+    fn options(self) -> &'static [&'static str] {
+        match self {
+            Self::BumpVersion => &["bump", "set", "deploy"],
+            Self::Deploy => &["force"],
+            Self::SetLatest => &["version"],
+            Self::Command => &["command"],
+            Self::Install | Self::Build | Self::Doctor => &[],
+        }
+    }
+}
+
+impl fmt::Display for StepKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// One `[[step]]` entry of a workflow file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub name: Option<String>,
+    pub run: StepKind,
+    // Run the step only when this condition holds, e.g. "!prerelease"
+    #[serde(rename = "if")]
+    pub condition: Option<String>,
+    // Carry on with the next step if this one fails
+    #[serde(default)]
+    pub continue_on_error: bool,
+    pub bump: Option<BumpType>,
+    pub set: Option<String>,
+    pub deploy: Option<DeployTarget>,
+    pub force: Option<bool>,
+    // Version or tag for set-latest, "{new_tag}" when not given
+    pub version: Option<String>,
+    pub command: Option<Vec<String>>,
+}
+
+impl Step {
+    // Name shown in the progress output and the summary
+    pub fn title(&self) -> String {
+        self.name.clone().unwrap_or_else(|| self.run.to_string())
+    }
+
+    // Options set on this step, for rejecting ones its kind ignores
+    fn options_set(&self) -> Vec<&'static str> {
+        [
+            ("bump", self.bump.is_some()),
+            ("set", self.set.is_some()),
+            ("deploy", self.deploy.is_some()),
+            ("force", self.force.is_some()),
+            ("version", self.version.is_some()),
+            ("command", self.command.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(option, set)| set.then_some(option))
+        .collect()
+    }
+
+    fn validate(&self, number: usize) -> Result<()> {
+        let invalid = |message: String| DocToolsError::Workflow(format!("step {} ({}): {}", number, self.title(), message));
+
+        if let Some(option) = self.options_set().into_iter().find(|option| !self.run.options().contains(option)) {
+            return Err(invalid(format!("'{}' does not apply to a {} step", option, self.run)));
+        }
+
+        match self.run {
+            StepKind::BumpVersion if self.bump.is_some() == self.set.is_some() => {
+                Err(invalid("set exactly one of 'bump' or 'set'".to_string()))
+            }
+            StepKind::Command if self.command.as_ref().is_none_or(Vec::is_empty) => {
+                Err(invalid("'command' needs at least the program to run".to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// A parsed workflow file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Workflow {
+    pub name: Option<String>,
+    // Initial variables; `--var NAME=VALUE` overrides them
+    #[serde(default)]
+    pub vars: BTreeMap<String, toml::Value>,
+    #[serde(rename = "step", default)]
+    pub steps: Vec<Step>,
+}
+
+impl Workflow {
+    pub fn parse(content: &str) -> Result<Self> {
+        let workflow: Self = toml::from_str(content).map_err(|e| DocToolsError::Workflow(e.message().to_string()))?;
+
+        if workflow.steps.is_empty() {
+            return Err(DocToolsError::Workflow("no [[step]] entries".to_string()));
+        }
+        for (index, step) in workflow.steps.iter().enumerate() {
+            step.validate(index + 1)?;
+        }
+        Ok(workflow)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| DocToolsError::io(format!("Failed to read {}", path.display()), e))?;
+
+        Self::parse(&content).map_err(|e| match e {
+            DocToolsError::Workflow(message) => DocToolsError::Workflow(format!("{}: {}", path.display(), message)),
+            other => other,
+        })
+    }
+
+    // Find a workflow given on the command line: a path, or a file in the
+    // project root with or without its .toml extension
+    pub fn locate(project: &Project, workflow: &str) -> Result<PathBuf> {
+        let candidates = [
+            PathBuf::from(workflow),
+            project.root().join(workflow),
+            project.root().join(format!("{}.toml", workflow)),
+        ];
+
+        candidates
+            .iter()
+            .find(|path| path.is_file())
+            .cloned()
+            .ok_or_else(|| DocToolsError::MissingFile(project.root().join(workflow)))
+    }
+
+    // Run every step in order; the result carries the first error that
+    // stopped the workflow
+    pub fn run(&self, ctx: &Context, overrides: &[(String, String)]) -> (WorkflowReport, Result<()>) {
+        let mut vars = Variables::initial(ctx);
+        for (name, value) in &self.vars {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            vars.set(name, value);
+        }
+        for (name, value) in overrides {
+            vars.set(name, value.clone());
+        }
+
+        let mut report = WorkflowReport {
+            workflow: self.name.clone().unwrap_or_else(|| "workflow".to_string()),
+            steps: Vec::new(),
+            variables: BTreeMap::new(),
+        };
+        let mut failure = None;
+
+        for (index, step) in self.steps.iter().enumerate() {
+            let mut result = StepResult {
+                name: step.title(),
+                run: step.run,
+                status: StepStatus::NotRun,
+                duration_ms: 0,
+                detail: None,
+            };
+
+            if failure.is_some() {
+                report.steps.push(result);
+                continue;
+            }
+
+            let condition = step.condition.as_deref().map(|condition| vars.evaluate(condition));
+            match condition {
+                Some(Ok(false)) => {
+                    result.status = StepStatus::Skipped;
+                    result.detail = step.condition.clone();
+                    info!(
+                        "\n{}[{}/{}] {}{} skipped, condition '{}' is false",
+                        BLUE,
+                        index + 1,
+                        self.steps.len(),
+                        result.name,
+                        NC,
+                        result.detail.as_deref().unwrap_or_default()
+                    );
+                    report.steps.push(result);
+                    continue;
+                }
+                Some(Err(e)) => {
+                    result.status = StepStatus::Failed;
+                    result.detail = Some(e.to_string());
+                    failure = Some((result.name.clone(), e));
+                    report.steps.push(result);
+                    continue;
+                }
+                Some(Ok(true)) | None => {}
+            }
+
+            info!("\n{}[{}/{}] {}{}", BLUE, index + 1, self.steps.len(), result.name, NC);
+            let started = Instant::now();
+            let outcome = run_step(ctx, step, &mut vars);
+            result.duration_ms = started.elapsed().as_millis().try_into().unwrap_or(u64::MAX);
+
+            match outcome {
+                Ok(()) => result.status = StepStatus::Ok,
+                Err(e) => {
+                    result.status = StepStatus::Failed;
+                    result.detail = Some(e.to_string());
+                    if !step.continue_on_error {
+                        failure = Some((result.name.clone(), e));
+                    }
+                }
+            }
+            report.steps.push(result);
+        }
+
+        report.variables = vars.values;
+        let failure = failure.map(|(name, source)| DocToolsError::Step {
+            name,
+            source: Box::new(source),
+            steps: report.steps.clone(),
+        });
+        (report, failure.map_or(Ok(()), Err))
+    }
+}
+
+fn run_step(ctx: &Context, step: &Step, vars: &mut Variables) -> Result<()> {
+    match step.run {
+        StepKind::Install => Startup::new(ctx, None).install_dependencies(),
+        StepKind::Build => Startup::new(ctx, None).build_site(),
+        StepKind::Doctor => {
+            let report = Doctor::new(ctx).run();
+            let failed: Vec<&str> = report
+                .checks
+                .iter()
+                .filter(|check| check.outcome == Outcome::Fail)
+                .map(|check| check.name.as_str())
+                .collect();
+
+            if !failed.is_empty() {
+                return Err(DocToolsError::Workflow(format!(
+                    "doctor checks failed: {} (run 'doc-cli doctor' for details)",
+                    failed.join(", ")
+                )));
+            }
+            info!("All {} doctor checks passed or warned.", report.checks.len());
+            Ok(())
+        }
+        StepKind::BumpVersion => {
+            let options = BumpOptions {
+                bump: step.bump,
+                set_version: step.set.as_deref().map(|set| vars.expand(set)).transpose()?,
+                yes: true,
+                deploy: Some(step.deploy.unwrap_or(DeployTarget::None)),
+            };
+
+            let report = VersionBumper::new(ctx, options).run()?;
+            vars.set("previous_version", report.previous_version);
+            vars.set("new_version", report.new_version);
+            vars.set("new_tag", report.tag);
+            Ok(())
+        }
+        StepKind::Deploy => {
+            Deployer::new(ctx, step.force.unwrap_or(false))?.run()?.check()
+        }
+        StepKind::SetLatest => {
+            let version = vars.expand(step.version.as_deref().unwrap_or("{new_tag}"))?;
+            let prefix = &ctx.config.tag_prefix.value;
+            let tag = if version.starts_with(prefix.as_str()) { version } else { ctx.config.tag_name(&version) };
+
+            let deployer = Deployer::new(ctx, false)?;
+            deployer.point_latest_at(&tag)?;
+            deployer.push_gh_pages()
+        }
+        StepKind::Command => {
+            let words = step
+                .command
+                .as_deref()
+                .unwrap_or_default()
+                .iter()
+                .map(|word| vars.expand(word))
+                .collect::<Result<Vec<_>>>()?;
+            let Some((program, args)) = words.split_first() else {
+                return Ok(());
+            };
+
+            let cmd = Cmd::new(program, ctx.project.root()).args(args).mutating();
+            info!("Running {}", cmd);
+            let output = ctx.runner.run(&cmd)?;
+            if !output.success() {
+                let status = output.code.map_or_else(|| "a signal".to_string(), |code| format!("status {}", code));
+                return Err(DocToolsError::Workflow(format!("{} exited with {}", cmd, status)));
+            }
+            Ok(())
+        }
+    }
+}
+
+// Variables available to `{name}` placeholders and conditions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Variables {
+    values: BTreeMap<String, String>,
+}
+
+impl Variables {
+    // Built-in variables: the current version and tag, the deployment branch
+    // and remote, and `prerelease` (false unless overridden)
+    pub fn initial(ctx: &Context) -> Self {
+        let version = VersionBumper::get_latest_tag(ctx.runner.as_ref(), ctx.project.root(), &ctx.config.tag_prefix.value);
+
+        let mut vars = Self::default();
+        vars.set("current_tag", ctx.config.tag_name(&version));
+        vars.set("current_version", version);
+        vars.set("branch", ctx.config.gh_pages_branch.value.clone());
+        vars.set("remote", ctx.config.remote.value.clone());
+        vars.set("prerelease", "false".to_string());
+        vars
+    }
+
+    pub fn set(&mut self, name: &str, value: String) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn lookup(&self, name: &str) -> Result<&str> {
+        self.get(name).ok_or_else(|| {
+            let known: Vec<&str> = self.values.keys().map(String::as_str).collect();
+            DocToolsError::Workflow(format!("unknown variable '{}' (known: {})", name, known.join(", ")))
+        })
+    }
+
+    // Replace every {name} in `template`; {{ and }} stand for literal braces
+    pub fn expand(&self, template: &str) -> Result<String> {
+        let mut expanded = String::new();
+        let mut rest = template;
+
+        while let Some(start) = rest.find(['{', '}']) {
+            expanded.push_str(&rest[..start]);
+            let brace = &rest[start..];
+
+            if brace.starts_with("{{") || brace.starts_with("}}") {
+                expanded.push_str(&brace[..1]);
+                rest = &brace[2..];
+            } else if let (true, Some(end)) = (brace.starts_with('{'), brace.find('}')) {
+                expanded.push_str(self.lookup(brace[1..end].trim())?);
+                rest = &brace[end + 1..];
+            } else {
+                return Err(DocToolsError::Workflow(format!("unbalanced brace in '{}'", template)));
+            }
+        }
+        expanded.push_str(rest);
+        Ok(expanded)
+    }
+
+    // Evaluate a step condition: `name` and `!name` test whether a variable is
+    // true, `name == value` and `name != value` compare it with a literal
+    pub fn evaluate(&self, condition: &str) -> Result<bool> {
+        let condition = condition.trim();
+
+        for (operator, equal) in [("==", true), ("!=", false)] {
+            if let Some((name, value)) = condition.split_once(operator) {
+                let value = self.expand(value.trim().trim_matches(['"', '\'']))?;
+                return Ok((self.lookup(name.trim())? == value) == equal);
+            }
+        }
+
+        match condition.strip_prefix('!') {
+            Some(name) => Ok(!truthy(self.lookup(name.trim())?)),
+            None => Ok(truthy(self.lookup(condition)?)),
+        }
+    }
+}
+
+// Whether a variable value counts as true in a condition
+fn truthy(value: &str) -> bool {
+    !matches!(value.trim().to_ascii_lowercase().as_str(), "" | "false" | "0" | "no")
+}
+
+// How a step ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Ok,
+    // Its condition was false
+    Skipped,
+    Failed,
+    // An earlier step stopped the workflow
+    NotRun,
+}
+
+impl fmt::Display for StepStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Ok => "ok",
+            Self::Skipped => "skipped",
+            Self::Failed => "failed",
+            Self::NotRun => "not run",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StepResult {
+    pub name: String,
+    pub run: StepKind,
+    pub status: StepStatus,
+    pub duration_ms: u64,
+    // The condition of a skipped step or the error of a failed one
+    pub detail: Option<String>,
+}
+
+// What a workflow run did, also the `--output json` result of `run`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkflowReport {
+    pub workflow: String,
+    pub steps: Vec<StepResult>,
+    // Variables as they were when the workflow ended
+    pub variables: BTreeMap<String, String>,
+}
+
+impl WorkflowReport {
+    pub fn succeeded(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| matches!(step.status, StepStatus::Ok | StepStatus::Skipped))
+    }
+}
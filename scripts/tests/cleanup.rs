@@ -0,0 +1,44 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use doc_tools::cleanup::{BranchGuard, ChildGuard};
+use doc_tools::exec::{CommandOutput, FakeRunner};
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn dropped_branch_guard_switches_back() {
+    let runner = Arc::new(FakeRunner::new());
+
+    {
+        let _guard = BranchGuard::checkout(runner.clone(), root(), "feature", "main").unwrap();
+        // An early return or `?` leaves the scope here without restore()
+    }
+
+    assert_eq!(runner.command_lines(), ["git checkout main", "git checkout feature"]);
+}
+
+#[test]
+fn detached_head_is_restored_by_commit() {
+    let runner = Arc::new(FakeRunner::new().on("git rev-parse HEAD", CommandOutput::ok("0123abcd\n")));
+
+    let guard = BranchGuard::checkout(runner.clone(), root(), "HEAD", "main").unwrap();
+    guard.restore().unwrap();
+
+    assert_eq!(
+        runner.command_lines(),
+        ["git rev-parse HEAD", "git checkout main", "git checkout 0123abcd"]
+    );
+}
+
+#[test]
+fn child_guard_terminates_unless_disarmed() {
+    let runner = Arc::new(FakeRunner::new());
+
+    ChildGuard::new(runner.clone(), root(), 4242).disarm();
+    drop(ChildGuard::new(runner.clone(), root(), 4343));
+
+    assert_eq!(runner.command_lines(), ["kill -TERM 4343"]);
+}
@@ -0,0 +1,38 @@
+// Helpers shared by the integration tests
+
+use std::env;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+// A directory under the system temp dir, removed again when dropped so
+// failing tests do not leave it behind
+pub struct Scratch(PathBuf);
+
+// A fresh empty directory; `name` must be unique across the test binaries
+pub fn scratch(name: &str) -> Scratch {
+    let dir = env::temp_dir().join(format!("doc-tools-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    Scratch(dir)
+}
+
+impl Deref for Scratch {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for Scratch {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
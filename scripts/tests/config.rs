@@ -0,0 +1,27 @@
+mod common;
+
+use std::fs;
+
+use doc_tools::config::Source;
+use doc_tools::{Config, Project};
+
+#[test]
+fn reads_the_file_and_rejects_unknown_keys() {
+    let root = common::scratch("config");
+    let project = Project::at(&root).unwrap();
+    let path = root.join("doc-tools.toml");
+
+    fs::write(&path, "[server]\nport = 9000\n\n[deploy]\nremote = \"upstream\"\n").unwrap();
+    let config = Config::load(&project, None, &["deploy.remote=fork".into()]).unwrap();
+    assert_eq!(config.port.value, 9000);
+    assert_eq!(config.port.source, Source::File(path.clone()));
+    assert_eq!((config.remote.value.as_str(), &config.remote.source), ("fork", &Source::Cli));
+
+    fs::write(&path, "[server]\nprot = 9000\n").unwrap();
+    let error = Config::load(&project, None, &[]).unwrap_err();
+    assert_eq!(error.kind(), "config");
+    assert!(error.to_string().contains("unknown key 'server.prot' (known keys: server.port, "));
+
+    fs::write(&path, "port = 9000\n").unwrap();
+    assert!(Config::load(&project, None, &[]).unwrap_err().to_string().contains("unknown key 'port'"));
+}
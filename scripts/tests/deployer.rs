@@ -0,0 +1,95 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::{Config, Context, Deployer, DocToolsError, Project};
+
+// A repository on `feature` with three tags, two of them already on gh-pages
+fn scripted_repo() -> FakeRunner {
+    FakeRunner::new()
+        .on("git rev-parse --abbrev-ref HEAD", CommandOutput::ok("feature\n"))
+        .on("git tag --sort=v:refname", CommandOutput::ok("v0.1.0\nv0.2.0\nv0.3.0\n"))
+        .on("git ls-tree --name-only gh-pages", CommandOutput::ok("index.html\nv0.1.0\nv0.2.0\nversions.json\n"))
+        .on("git show gh-pages:versions.json", CommandOutput::failed(128))
+}
+
+fn context(runner: &Arc<FakeRunner>) -> Context {
+    let project = Project::at(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+    Context::new(project, Config::default(), runner.clone())
+}
+
+fn mike_deploys(runner: &FakeRunner) -> Vec<String> {
+    runner
+        .command_lines()
+        .into_iter()
+        .filter(|line| line.starts_with("mike deploy"))
+        .collect()
+}
+
+#[test]
+fn smart_deploy_skips_versions_listed_by_ls_tree() {
+    let runner = Arc::new(scripted_repo());
+    let mut deployer = Deployer::new(&context(&runner), false).unwrap();
+
+    let report = deployer.run().unwrap();
+
+    assert_eq!(report.deployed, ["v0.3.0"]);
+    assert_eq!(report.skipped, ["v0.1.0", "v0.2.0"]);
+    assert_eq!(report.latest.as_deref(), Some("v0.3.0"));
+    assert_eq!(
+        mike_deploys(&runner),
+        [
+            "mike deploy v0.3.0 --branch gh-pages",
+            "mike deploy v0.3.0 latest --branch gh-pages --update-aliases",
+        ]
+    );
+}
+
+#[test]
+fn force_deploy_redeploys_every_tag_without_listing_gh_pages() {
+    let runner = Arc::new(scripted_repo());
+    let mut deployer = Deployer::new(&context(&runner), true).unwrap();
+
+    let report = deployer.run().unwrap();
+
+    assert_eq!(report.deployed, ["v0.1.0", "v0.2.0", "v0.3.0"]);
+    assert!(report.skipped.is_empty());
+    assert!(!runner.command_lines().iter().any(|line| line.starts_with("git ls-tree")));
+}
+
+#[test]
+fn tags_are_read_on_main_and_the_original_branch_is_restored() {
+    let runner = Arc::new(scripted_repo());
+    let mut deployer = Deployer::new(&context(&runner), false).unwrap();
+
+    deployer.run().unwrap();
+
+    let checkouts: Vec<String> = runner
+        .command_lines()
+        .into_iter()
+        .filter(|line| line.starts_with("git checkout"))
+        .collect();
+    assert_eq!(checkouts, ["git checkout main", "git checkout feature"]);
+}
+
+#[test]
+fn versions_mike_fails_to_deploy_are_reported_and_the_rest_continue() {
+    let runner = Arc::new(scripted_repo().on("mike deploy v0.3.0 --branch", CommandOutput::failed(1)));
+    let mut deployer = Deployer::new(&context(&runner), true).unwrap();
+
+    let report = deployer.run().unwrap();
+
+    assert_eq!(report.deployed, ["v0.1.0", "v0.2.0"]);
+    assert_eq!(report.failed, ["v0.3.0"]);
+    assert_eq!(report.check().unwrap_err().to_string(), "mike: Failed to deploy v0.3.0.");
+}
+
+#[test]
+fn failed_push_is_reported_as_a_git_error() {
+    let runner = Arc::new(scripted_repo().on("git push origin gh-pages", CommandOutput::failed(1)));
+    let mut deployer = Deployer::new(&context(&runner), false).unwrap();
+
+    let result = deployer.run();
+
+    assert!(matches!(result, Err(DocToolsError::Git(_))));
+}
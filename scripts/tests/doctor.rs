@@ -0,0 +1,85 @@
+mod common;
+
+use std::fs;
+use std::sync::Arc;
+
+use doc_tools::doctor::{self, Doctor, Outcome, Requirement};
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::{Config, Context, Project};
+
+use common::Scratch;
+
+// A project with two requirements and a .env that still has a placeholder
+fn scratch_project(name: &str) -> Scratch {
+    let root = common::scratch(&format!("doctor-{}", name));
+    fs::write(root.join("requirements.txt"), "mkdocs>=1.6.1\n\nmike>=2.1.3  # versioning\n").unwrap();
+    fs::write(root.join(".env.example"), "# key\nOPENAI_API_KEY=your_key_here\nAI_MODEL=gpt-4\n").unwrap();
+    fs::write(root.join(".env"), "OPENAI_API_KEY=your_key_here\nAI_MODEL=gpt-4\n").unwrap();
+    root
+}
+
+#[test]
+fn reports_outdated_packages_missing_remotes_and_placeholders() {
+    let root = scratch_project("report");
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("python3 -c", CommandOutput::ok("mkdocs 1.5.3\nmike 2.1.3\n"))
+            .on("python3 -c 'import sys;", CommandOutput::ok("3.11.4\n"))
+            .on("git rev-parse --verify", CommandOutput::failed(1))
+            .on("git remote get-url origin", CommandOutput::failed(2)),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let report = Doctor::new(&ctx).run();
+    let outcome = |name: &str| report.checks.iter().find(|check| check.name == name).unwrap();
+
+    assert_eq!(outcome("mkdocs").outcome, Outcome::Pass);
+    assert_eq!(outcome("requirements").detail, "mkdocs 1.5.3 < 1.6.1");
+    assert_eq!(outcome("requirements").outcome, Outcome::Fail);
+    assert_eq!(outcome("remote").outcome, Outcome::Fail);
+    assert_eq!(outcome("deploy branch").outcome, Outcome::Warn);
+    assert_eq!(outcome(".env").detail, "OPENAI_API_KEY still set to the example value");
+    assert_eq!(report.failures, 2);
+    assert!(!report.healthy());
+
+    // Without a venv the checks use the interpreter startup would pick
+    assert!(!runner.command_lines().iter().any(|line| line.starts_with("python ")));
+
+    // Diagnostics never change anything
+    assert!(runner.calls().iter().all(|cmd| cmd.effect == doc_tools::exec::Effect::ReadOnly));
+}
+
+#[test]
+fn reports_when_no_python_is_new_enough() {
+    let root = scratch_project("old-python");
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("python3 -c", CommandOutput::ok("3.8.10\n"))
+            .on("python -c", CommandOutput::failed(127)),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner);
+
+    let report = Doctor::new(&ctx).run();
+    let python = report.checks.iter().find(|check| check.name == "python").unwrap();
+    let mkdocs = report.checks.iter().find(|check| check.name == "mkdocs").unwrap();
+
+    assert_eq!(python.outcome, Outcome::Fail);
+    assert!(python.detail.contains("(python3 is 3.8.10, python not found)"));
+    assert_eq!(mkdocs.outcome, Outcome::Fail);
+}
+
+#[test]
+fn parses_requirement_minimums() {
+    let requirements = doctor::parse_requirements("mkdocs>=1.6.1\n-e .\nrequests[socks]\njinja2==3.1.2 ; python_version>'3'\n");
+
+    assert_eq!(
+        requirements,
+        [
+            Requirement { name: "mkdocs".into(), minimum: Some("1.6.1".into()) },
+            Requirement { name: "requests".into(), minimum: None },
+            Requirement { name: "jinja2".into(), minimum: Some("3.1.2".into()) },
+        ]
+    );
+    assert!(doctor::version_at_least("1.10.0", "1.9"));
+    assert!(!doctor::version_at_least("2.1.2rc1", "2.1.3"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::sync::Arc;
+
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::plugins::{self, PROJECT_ROOT_ENV};
+use doc_tools::{Config, Context, DocToolsError, Project};
+
+use common::Scratch;
+
+// A project whose release directory holds a plugin, a build artifact and a
+// plugin shadowed by a built-in command
+fn project_with_plugins(name: &str) -> Scratch {
+    let root = common::scratch(&format!("plugins-{}", name));
+    let release = root.join("scripts/target/release");
+    fs::create_dir_all(&release).unwrap();
+
+    for (name, mode) in [("doc-cli-hello", 0o755), ("doc-cli-hello.d", 0o644), ("doc-cli-deploy", 0o755)] {
+        let path = release.join(name);
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+    }
+    root
+}
+
+fn context(root: &Path, runner: &Arc<FakeRunner>) -> Context {
+    Context::new(Project::at(root).unwrap(), Config::default(), runner.clone())
+}
+
+#[test]
+fn discovers_plugins_and_passes_the_project_root() {
+    let root = project_with_plugins("discover");
+    let plugins = plugins::discover(Some(&root));
+    let hello = plugins.iter().find(|plugin| plugin.name == "hello").unwrap();
+
+    assert!(plugins.iter().all(|plugin| plugin.name != "deploy" && plugin.name != "hello.d"));
+
+    let runner = Arc::new(FakeRunner::new());
+    let ctx = context(&root, &runner);
+    hello.run(&ctx, &["--flag".to_string()]).unwrap();
+
+    let call = &runner.calls()[0];
+    assert_eq!(call.args, ["--flag"]);
+    assert!(call.program.ends_with("scripts/target/release/doc-cli-hello"));
+    assert_eq!(
+        call.env,
+        [(PROJECT_ROOT_ENV.to_string(), ctx.project.root().display().to_string())]
+    );
+}
+
+#[test]
+fn failing_plugin_passes_on_its_exit_code() {
+    let root = project_with_plugins("exit-code");
+    let hello = plugins::discover(Some(&root)).into_iter().find(|plugin| plugin.name == "hello").unwrap();
+
+    let runner = Arc::new(FakeRunner::new().on(hello.path.to_string_lossy(), CommandOutput::failed(3)));
+    let error = hello.run(&context(&root, &runner), &[]).unwrap_err();
+
+    assert!(matches!(error, DocToolsError::Plugin { code: 3, .. }));
+    assert_eq!(error.exit_code(), 3);
+}
+
+#[test]
+fn unknown_commands_suggest_the_closest_name() {
+    let error = plugins::unknown_command("deplyo", &[]);
+    assert_eq!(error.to_string(), "unrecognized subcommand 'deplyo'; did you mean 'deploy'?");
+}
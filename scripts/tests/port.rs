@@ -0,0 +1,73 @@
+use std::net::TcpListener;
+
+use doc_tools::port::{self, Listener, PortSpec, Socket};
+
+// Two listeners on 8000 (one owned by uid 1000), a connection from 8000 and a listener on 8001
+const PROC_NET_TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F40 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 48213 1 0000000000000000 100 0 0 10 0
+   1: 00000000:1F40 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 48990 1 0000000000000000 100 0 0 10 0
+   2: 0100007F:1F40 0100007F:D2F2 01 00000000:00000000 00:00000000 00000000  1000        0 50111 1 0000000000000000 20 4 30 10 -1
+   3: 0100007F:1F41 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 51002 1 0000000000000000 100 0 0 10 0
+";
+
+#[test]
+fn finds_listening_sockets_in_proc_net_tcp() {
+    assert_eq!(
+        port::listening_sockets(PROC_NET_TCP, 8000),
+        [Socket { uid: 1000, inode: 48213 }, Socket { uid: 0, inode: 48990 }]
+    );
+    assert!(port::listening_sockets(PROC_NET_TCP, 8002).is_empty());
+    assert!(port::listening_sockets("", 8000).is_empty());
+}
+
+#[test]
+fn parses_port_specs() {
+    assert_eq!("8001".parse(), Ok(PortSpec::Fixed(8001)));
+    assert_eq!("auto".parse(), Ok(PortSpec::Auto));
+    assert!("0".parse::<PortSpec>().is_err());
+    assert!("70000".parse::<PortSpec>().is_err());
+    assert!("eight".parse::<PortSpec>().is_err());
+}
+
+#[test]
+fn finds_the_process_holding_a_port() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let taken = listener.local_addr().unwrap().port();
+
+    assert!(!port::is_free("127.0.0.1", taken));
+    assert_ne!(port::next_free("127.0.0.1", taken), Some(taken));
+    let holders = port::listeners(taken);
+    assert!(holders.iter().any(|holder| holder.pid == std::process::id()));
+}
+
+#[test]
+fn recognizes_documentation_servers() {
+    let listener = |cmdline: &str| Listener {
+        pid: 4242,
+        uid: 1000,
+        name: "python3".into(),
+        cmdline: cmdline.into(),
+    };
+
+    assert!(listener("/home/dev/site/.venv/bin/python -m mkdocs serve --dev-addr=127.0.0.1:8000").is_doc_server());
+    assert!(listener("python3 -m http.server 8000 --bind 127.0.0.1").is_doc_server());
+    assert!(listener("/usr/bin/python3 /usr/local/bin/mike serve").is_doc_server());
+    assert!(listener("/home/dev/site/.venv/bin/python3 -u /home/dev/site/.venv/bin/mkdocs serve").is_doc_server());
+    assert!(listener("/home/dev/site/scripts/target/release/doc-cli serve site --port 8000").is_doc_server());
+    assert!(listener("doc-cli startup --draft-version 1.2.0").is_doc_server());
+    assert!(listener("/srv/docs/scripts/target/release/doc-cli -C /srv/docs --color=never -v startup").is_doc_server());
+    assert!(listener("doc-cli -c server.port=8001 serve site").is_doc_server());
+    assert!(!listener("doc-cli --verbose deploy serve").is_doc_server());
+    assert!(!listener("doc-cli --project-root startup deploy").is_doc_server());
+    assert!(!listener("vim mike").is_doc_server());
+    assert!(!listener("vim -m mkdocs").is_doc_server());
+    assert!(!listener("python3 app.py --docs /srv/mkdocs").is_doc_server());
+    assert!(!listener("python3 tools/sync.py -m mkdocs").is_doc_server());
+    assert!(!listener("python3 manage.py runserver 8000").is_doc_server());
+    assert!(!listener("postgres -D /var/lib/postgresql").is_doc_server());
+
+    let passwd = "root:x:0:0:root:/root:/bin/bash\ndev:x:1000:1000::/home/dev:/bin/sh\n";
+    assert_eq!(port::user_name(passwd, 1000).as_deref(), Some("dev"));
+    assert_eq!(port::user_name(passwd, 1001), None);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::fs;
+
+use doc_tools::exec::FakeRunner;
+use doc_tools::rebuild::{Freshness, Sources};
+
+use common::Scratch;
+
+// A minimal copy of the crate layout with one already-built binary
+fn scratch_crate(name: &str) -> (Scratch, Sources) {
+    let dir = common::scratch(&format!("rebuild-{}", name));
+    fs::create_dir_all(dir.join("src/bin")).unwrap();
+    fs::write(dir.join("Cargo.toml"), "[package]\nname = \"doc-tools\"\n").unwrap();
+    fs::write(dir.join("src/lib.rs"), "// lib\n").unwrap();
+    fs::write(dir.join("src/bin/doc-cli.rs"), "fn main() {}\n").unwrap();
+
+    // Never the shared CARGO_TARGET_DIR, whose real binaries this would overwrite
+    let sources = Sources::with_target_dir(&dir, &dir.join("target"));
+    fs::create_dir_all(sources.release_dir()).unwrap();
+    for binary in sources.binaries() {
+        fs::write(binary, "").unwrap();
+    }
+    (dir, sources)
+}
+
+#[test]
+fn rebuilds_only_when_the_sources_change() {
+    let (dir, sources) = scratch_crate("rebuild");
+    let runner = FakeRunner::new();
+
+    let first = sources.rebuild(&runner, false, false).unwrap();
+    assert_eq!(first.freshness, Freshness::Unrecorded);
+    assert!(first.rebuilt);
+
+    let second = sources.rebuild(&runner, false, false).unwrap();
+    assert_eq!(second.freshness, Freshness::Fresh);
+    assert!(!second.rebuilt);
+
+    // Fingerprints must not change with the Rust toolchain that computes them
+    assert_eq!(first.fingerprint, "aa95f41314d797c4");
+
+    // Touching a file without changing it keeps the build fresh
+    fs::write(dir.join("src/lib.rs"), "// lib\n").unwrap();
+    assert_eq!(sources.freshness().unwrap(), Freshness::Fresh);
+
+    fs::write(dir.join("src/lib.rs"), "// changed\n").unwrap();
+    assert_eq!(sources.freshness().unwrap(), Freshness::Changed);
+    assert!(sources.rebuild(&runner, false, false).unwrap().rebuilt);
+
+    let cargo_runs = runner
+        .command_lines()
+        .iter()
+        .filter(|line| line.starts_with("cargo build --release"))
+        .count();
+    assert_eq!(cargo_runs, 2);
+}
+
+#[test]
+fn forced_rebuild_runs_cargo_even_when_fresh() {
+    let (_dir, sources) = scratch_crate("forced");
+    let runner = FakeRunner::new();
+
+    sources.rebuild(&runner, false, false).unwrap();
+    let report = sources.rebuild(&runner, true, false).unwrap();
+
+    assert_eq!(report.freshness, Freshness::Fresh);
+    assert!(report.rebuilt);
+    assert_eq!(runner.command_lines().len(), 2);
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use std::fs;
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::port::{self, PortSpec};
+use doc_tools::startup::Environment;
+use doc_tools::{Config, Context, Project, Startup};
+
+use common::Scratch;
+
+fn scratch_project(name: &str) -> Scratch {
+    let root = common::scratch(&format!("startup-{}", name));
+    fs::write(root.join("requirements.txt"), "mkdocs\n").unwrap();
+    root
+}
+
+// python3 is new enough to create the venv with
+fn runner() -> Arc<FakeRunner> {
+    Arc::new(FakeRunner::new().on("python3 -c", CommandOutput::ok("3.11.4\n")))
+}
+
+// A port nothing listens on, so nothing asks whether to kill its owner
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+#[test]
+fn local_mode_installs_and_serves_on_loopback() {
+    let root = scratch_project("local");
+    let runner = runner();
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let port = free_port();
+
+    let server = Startup::new(&ctx, None)
+        .in_environment(Environment::Local)
+        .port(Some(PortSpec::Fixed(port)))
+        .start()
+        .unwrap();
+    let report = server.report();
+
+    assert_eq!(report.environment, Environment::Local);
+    assert_eq!(report.url, format!("http://127.0.0.1:{}", port));
+    let venv = root.join(".venv");
+    let lines = runner.command_lines();
+    assert!(lines.contains(&format!("python3 -m venv {}", venv.display())));
+    assert!(lines.contains(&format!("{}/bin/python -m pip install -e .", venv.display())));
+    assert!(lines.iter().any(|line| line.contains(&format!("mkdocs serve --dev-addr=127.0.0.1:{}", port))));
+
+    server.wait().unwrap();
+}
+
+#[test]
+fn codespaces_mode_serves_on_every_interface() {
+    let root = scratch_project("codespaces");
+    let runner = runner();
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let port = free_port();
+
+    let server = Startup::new(&ctx, Some("1.2.0".into()))
+        .in_environment(Environment::Codespaces)
+        .port(Some(PortSpec::Fixed(port)))
+        .start()
+        .unwrap();
+
+    // Drafts are built, then served from a thread of this process
+    let report = server.report();
+    assert_eq!(report.bind, "0.0.0.0");
+    assert!(report.url.ends_with(&format!(":{}/1.2.0/", port)));
+    assert!(report.ready);
+    assert_eq!((report.pid, report.log), (None, None));
+    assert!(runner.command_lines().iter().any(|line| line.contains("-m mkdocs build")));
+    assert!(!port::is_free("0.0.0.0", port));
+
+    drop(server);
+    assert!(port::is_free("0.0.0.0", port));
+}
+
+#[test]
+fn auto_port_skips_ports_in_use() {
+    let root = scratch_project("auto-port");
+    let runner = runner();
+    let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut config = Config::default();
+    config.port.value = taken.local_addr().unwrap().port();
+    let ctx = Context::new(Project::at(&root).unwrap(), config.clone(), runner.clone());
+
+    let server = Startup::new(&ctx, None)
+        .in_environment(Environment::Local)
+        .port(Some(PortSpec::Auto))
+        .bind(Some("127.0.0.1".into()))
+        .start()
+        .unwrap();
+    let port = server.report().port;
+
+    assert!(port > config.port.value);
+    assert!(runner
+        .command_lines()
+        .iter()
+        .any(|line| line.contains(&format!("--dev-addr=127.0.0.1:{}", port))));
+
+    server.wait().unwrap();
+    drop(taken);
+}
+
+#[test]
+fn leaves_unknown_port_holders_alone() {
+    let root = scratch_project("port-holder");
+    let runner = runner();
+    let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = taken.local_addr().unwrap().port();
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    // The holder is this test binary, which is no documentation server
+    let error = Startup::new(&ctx, None)
+        .in_environment(Environment::Local)
+        .port(Some(PortSpec::Fixed(port)))
+        .check_port_and_kill_if_needed()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), "port_in_use");
+    assert!(error.to_string().contains(&format!("pid {}", std::process::id())));
+    assert!(error.to_string().contains("--force"));
+    assert!(!runner.command_lines().iter().any(|line| line.starts_with("kill")));
+
+    drop(taken);
+}
+
+#[test]
+fn refuses_pythons_older_than_ci() {
+    let root = scratch_project("old-python");
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("python3 -c", CommandOutput::ok("3.8.10\n"))
+            .on("python -c", CommandOutput::failed(127)),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let error = Startup::new(&ctx, None).install_dependencies().unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "python: Python 3.10 or newer is required (python3 is 3.8.10, python not found); \
+         install it or set python.executable in doc-tools.toml"
+    );
+    assert!(!runner.command_lines().iter().any(|line| line.contains("-m venv")));
+}
+
+#[test]
+fn recreate_clears_an_existing_venv() {
+    let root = scratch_project("recreate");
+    fs::create_dir_all(root.join(".venv/bin")).unwrap();
+    fs::write(root.join(".venv/bin/python"), "").unwrap();
+    let venv_python = format!("{} -c", root.join(".venv/bin/python").display());
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("python3 -c", CommandOutput::ok("3.11.4\n"))
+            .on(venv_python, CommandOutput::ok("3.12.1\n")),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    Startup::new(&ctx, None).install_dependencies().unwrap();
+    Startup::new(&ctx, None).recreate_venv(true).install_dependencies().unwrap();
+
+    let creates: Vec<String> = runner
+        .command_lines()
+        .into_iter()
+        .filter(|line| line.contains("-m venv"))
+        .collect();
+    assert_eq!(creates, [format!("python3 -m venv --clear {}", root.join(".venv").display())]);
+}
+
+#[test]
+fn skips_pip_until_requirements_change() {
+    let root = scratch_project("fingerprint");
+    fs::create_dir_all(root.join(".venv/bin")).unwrap();
+    fs::write(root.join(".venv/bin/python"), "").unwrap();
+    let venv_python = root.join(".venv/bin/python").display().to_string();
+    let runner = Arc::new(FakeRunner::new().on(format!("{} -c", venv_python), CommandOutput::ok("3.12.1\n")));
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+    let pip_runs = || {
+        runner
+            .command_lines()
+            .iter()
+            .filter(|line| line.starts_with(&format!("{} -m pip install -r", venv_python)))
+            .count()
+    };
+
+    Startup::new(&ctx, None).install_dependencies().unwrap();
+    Startup::new(&ctx, None).install_dependencies().unwrap();
+    assert_eq!(pip_runs(), 1);
+
+    Startup::new(&ctx, None).reinstall(true).install_dependencies().unwrap();
+    assert_eq!(pip_runs(), 2);
+
+    fs::write(root.join("requirements.txt"), "mkdocs>=1.6\n").unwrap();
+    Startup::new(&ctx, None).install_dependencies().unwrap();
+    assert_eq!(pip_runs(), 3);
+}
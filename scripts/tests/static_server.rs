@@ -0,0 +1,101 @@
+mod common;
+
+use std::fs;
+use std::io::{BufReader, Read};
+use std::os::unix::fs::symlink;
+
+use doc_tools::static_server::{Request, StaticServer};
+use flate2::read::GzDecoder;
+
+use common::Scratch;
+
+// A built site with an index, a stylesheet, a nested page and a 404 page
+fn scratch_site(name: &str) -> Scratch {
+    let root = common::scratch(&format!("static-{}", name));
+    fs::create_dir_all(root.join("guide")).unwrap();
+    fs::write(root.join("index.html"), "<h1>Home</h1>").unwrap();
+    fs::write(root.join("guide/index.html"), "<h1>Guide</h1>").unwrap();
+    fs::write(root.join("style.css"), "body { margin: 0; }\n".repeat(40)).unwrap();
+    fs::write(root.join("404.html"), "<h1>Lost</h1>").unwrap();
+    root
+}
+
+#[test]
+fn serves_files_with_types_gzip_and_etags() {
+    let root = scratch_site("files");
+    let server = StaticServer::new(root.to_path_buf());
+
+    let page = server.respond(&Request::new("GET", "/guide/?tab=1"));
+    assert_eq!(page.status, 200);
+    assert_eq!(page.get("Content-Type"), Some("text/html; charset=utf-8"));
+    assert_eq!(page.get("Cache-Control"), Some("no-cache"));
+    assert_eq!(page.body, b"<h1>Guide</h1>");
+
+    let css = server.respond(&Request::new("GET", "/style.css").header("Accept-Encoding", "br, gzip"));
+    assert_eq!(css.get("Content-Type"), Some("text/css; charset=utf-8"));
+    assert_eq!(css.get("Content-Encoding"), Some("gzip"));
+    let mut unzipped = String::new();
+    GzDecoder::new(css.body.as_slice()).read_to_string(&mut unzipped).unwrap();
+    assert_eq!(unzipped, "body { margin: 0; }\n".repeat(40));
+
+    let etag = css.get("ETag").unwrap();
+    let revalidated = server.respond(&Request::new("GET", "/style.css").header("If-None-Match", etag));
+    assert_eq!(revalidated.status, 304);
+    assert!(revalidated.body.is_empty());
+
+    let directory = server.respond(&Request::new("GET", "/guide"));
+    assert_eq!((directory.status, directory.get("Location")), (301, Some("/guide/")));
+}
+
+#[test]
+fn serves_drafts_under_their_version_path() {
+    let root = scratch_site("base");
+    let server = StaticServer::new(root.to_path_buf()).base_path(Some("1.2.0"));
+
+    let home = server.respond(&Request::new("GET", "/"));
+    assert_eq!((home.status, home.get("Location")), (302, Some("/1.2.0/")));
+    assert_eq!(server.respond(&Request::new("GET", "/1.2.0/")).body, b"<h1>Home</h1>");
+    assert_eq!(server.respond(&Request::new("GET", "/1.2.0/gu%69de/")).body, b"<h1>Guide</h1>");
+
+    let missing = server.respond(&Request::new("GET", "/1.2.0/nope.html"));
+    assert_eq!(missing.status, 404);
+    assert_eq!(missing.body, b"<h1>Lost</h1>");
+    assert_eq!(server.respond(&Request::new("GET", "/guide/")).status, 404);
+
+    assert_eq!(server.respond(&Request::new("GET", "/1.2.0/../../etc/passwd")).status, 400);
+    assert_eq!(server.respond(&Request::new("GET", "/1.2.0/%2e%2e/secret")).status, 400);
+    assert_eq!(server.respond(&Request::new("POST", "/1.2.0/")).status, 405);
+}
+
+#[test]
+fn refuses_symlinks_that_leave_the_site() {
+    let root = scratch_site("symlinks");
+    let outside = common::scratch("static-outside");
+    fs::write(outside.join("secret.txt"), "secret").unwrap();
+    symlink(outside.join("secret.txt"), root.join("leak.txt")).unwrap();
+    symlink(&*outside, root.join("elsewhere")).unwrap();
+    symlink(root.join("guide"), root.join("manual")).unwrap();
+    let server = StaticServer::new(root.to_path_buf());
+
+    assert_eq!(server.respond(&Request::new("GET", "/leak.txt")).status, 403);
+    assert_eq!(server.respond(&Request::new("GET", "/elsewhere/secret.txt")).status, 403);
+    assert_eq!(server.respond(&Request::new("GET", "/manual/")).body, b"<h1>Guide</h1>");
+}
+
+#[test]
+fn reads_requests_and_writes_responses() {
+    let raw = "HEAD /1.2.0/ HTTP/1.1\r\nHost: localhost:8000\r\naccept-encoding: gzip\r\n\r\n";
+    let request = Request::read(&mut BufReader::new(raw.as_bytes())).unwrap().unwrap();
+    assert_eq!((request.method.as_str(), request.target.as_str()), ("HEAD", "/1.2.0/"));
+    assert_eq!(request.get("Accept-Encoding"), Some("gzip"));
+    assert!(Request::read(&mut BufReader::new(&b""[..])).unwrap().is_none());
+
+    let root = scratch_site("wire");
+    let response = StaticServer::new(root.to_path_buf()).respond(&request);
+    let mut wire = Vec::new();
+    response.write_to(&mut wire, true).unwrap();
+    let wire = String::from_utf8(wire).unwrap();
+    assert!(wire.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    assert!(wire.contains("Content-Length: 13\r\n"));
+    assert!(wire.ends_with("\r\n\r\n"));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::sync::Arc;
+
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::status::{self, StatusReport, VersionEntry, VersionsDrift};
+use doc_tools::{Config, Context, Project};
+
+const DEPLOYED: &str = r#"[
+  {"version": "0.2.0", "title": "0.2.0", "aliases": ["latest"]},
+  {"version": "0.1.0", "title": "0.1.0", "aliases": []}
+]"#;
+
+#[test]
+fn reads_both_versions_json_layouts() {
+    let project = status::parse_versions_file(r#"{"versions": [{"version": "v0.1.0", "aliases": ["latest"]}]}"#).unwrap();
+    let deployed = status::parse_versions_file(DEPLOYED).unwrap();
+
+    assert_eq!(
+        project,
+        [VersionEntry { version: "v0.1.0".into(), aliases: vec!["latest".into()] }]
+    );
+    assert_eq!(deployed.len(), 2);
+    assert!(status::parse_versions_file("not json").is_none());
+
+    let drift = VersionsDrift::compare(Some(&project), Some(&deployed), "v");
+    assert_eq!(drift.only_deployed, ["0.2.0"]);
+    assert!(drift.only_local.is_empty());
+    assert_eq!(drift.local_latest.as_deref(), Some("v0.1.0"));
+    assert_eq!(drift.deployed_latest.as_deref(), Some("0.2.0"));
+    assert!(!drift.in_sync());
+    assert!(!VersionsDrift::compare(None, None, "v").in_sync());
+}
+
+#[test]
+fn gathers_tags_deployments_and_versions_json() {
+    let root = common::scratch("status");
+
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("git describe --tags", CommandOutput::ok("v0.2.0\n"))
+            .on("git rev-list --count v0.2.0..HEAD", CommandOutput::ok("3\n"))
+            .on("git ls-tree --name-only gh-pages", CommandOutput::ok("v0.1.0\nindex.html\nversions.json\n"))
+            .on("git tag --sort=v:refname", CommandOutput::ok("v0.1.0\nv0.2.0\n"))
+            .on("git show gh-pages:versions.json", CommandOutput::ok(DEPLOYED)),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let report = StatusReport::gather(&ctx);
+
+    assert_eq!(report.latest_tag.as_deref(), Some("v0.2.0"));
+    assert_eq!(report.commits_since_tag, Some(3));
+    assert_eq!(report.deployed_versions, ["v0.1.0"]);
+    assert_eq!(report.undeployed_tags, ["v0.2.0"]);
+    assert_eq!(report.latest_alias.as_deref(), Some("0.2.0"));
+    assert!(!report.versions_json.local_found);
+    assert!(report.versions_json.deployed_found);
+    assert!(runner.calls().iter().all(|cmd| cmd.effect == doc_tools::exec::Effect::ReadOnly));
+}
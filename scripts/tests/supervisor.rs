@@ -0,0 +1,84 @@
+mod common;
+
+use std::fs;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use doc_tools::exec::{Cmd, CommandOutput, FakeRunner, SystemRunner};
+use doc_tools::supervisor::{Start, Supervisor, START_ATTEMPTS};
+
+// A port nothing answers on, so the site is never ready
+fn closed_port() -> (String, u16) {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    ("127.0.0.1".to_string(), port)
+}
+
+// Counts its runs in ./runs and crashes on the first `crashes` of them
+fn flaky_server(dir: &Path, crashes: u32, first_run_lasts: &str) -> Cmd {
+    let script = format!(
+        "n=$(cat runs 2>/dev/null || echo 0); echo $((n + 1)) > runs; echo \"run $n\"; \
+         if [ $n -eq 0 ]; then sleep {}; fi; [ $n -ge {} ]",
+        first_run_lasts, crashes
+    );
+    Cmd::new("sh", dir).arg("-c").arg(script).mutating()
+}
+
+#[test]
+fn gives_up_when_the_server_never_comes_up() {
+    let dir = common::scratch("supervisor-gives-up");
+    let runner = Arc::new(FakeRunner::new().on("sh -c", CommandOutput::failed(1)));
+    let serve = Cmd::new("sh", &dir).args(["-c", "exec mkdocs serve"]).mutating();
+
+    let mut supervisor = Supervisor::new(runner.clone(), serve, closed_port(), Some(dir.join("logs/server.log")))
+        .backoff(Duration::from_millis(1), Duration::from_millis(2));
+    let error = supervisor.start().unwrap_err();
+
+    assert!(error.to_string().contains("exited with status 1 3 times before the site was ready"));
+    assert_eq!(runner.calls().len(), START_ATTEMPTS as usize);
+    assert!(dir.join("logs/server.log").is_file());
+}
+
+#[test]
+fn restarts_crashed_servers_and_keeps_their_output() {
+    let dir = common::scratch("supervisor-restart");
+    let log = dir.join("server.log");
+    let runner = Arc::new(SystemRunner::new());
+
+    // The first run outlives the ready timeout, then crashes; the second exits cleanly
+    let mut supervisor = Supervisor::new(runner, flaky_server(&dir, 1, "0.5"), closed_port(), Some(log.clone()))
+        .backoff(Duration::from_millis(10), Duration::from_millis(20))
+        .ready_timeout(Duration::from_millis(100));
+
+    assert_eq!(supervisor.start().unwrap(), Start::NotReady);
+    supervisor.supervise().unwrap();
+
+    assert_eq!(fs::read_to_string(dir.join("runs")).unwrap(), "2\n");
+    assert_eq!(fs::read_to_string(&log).unwrap(), "run 0\nrun 1\n");
+}
+
+#[test]
+fn ready_once_the_site_answers() {
+    let dir = common::scratch("supervisor-ready");
+    let site = TcpListener::bind("127.0.0.1:0").unwrap();
+    let probe = ("127.0.0.1".to_string(), site.local_addr().unwrap().port());
+    thread::spawn(move || {
+        for mut stream in site.incoming().flatten() {
+            let mut request = [0; 512];
+            let _ = stream.read(&mut request);
+            let _ = stream.write_all(b"HTTP/1.0 200 OK\r\n\r\n");
+        }
+    });
+
+    // A server that keeps running; dropping the supervisor stops it
+    let serve = Cmd::new("sleep", &dir).arg("30").mutating();
+    let mut supervisor = Supervisor::new(Arc::new(SystemRunner::new()), serve, probe, None);
+
+    assert_eq!(supervisor.start().unwrap(), Start::Ready);
+    assert!(supervisor.pid().is_some());
+
+    drop(supervisor);
+}
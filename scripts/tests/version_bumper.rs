@@ -0,0 +1,49 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use doc_tools::bump_version::{BumpOptions, BumpType, DeployTarget};
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::{Config, Context, DocToolsError, Project, VersionBumper};
+
+fn context(runner: &Arc<FakeRunner>) -> Context {
+    let project = Project::at(Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap();
+    Context::new(project, Config::default(), runner.clone())
+}
+
+fn repo_at(tag: &str) -> Arc<FakeRunner> {
+    Arc::new(FakeRunner::new().on("git describe --tags", CommandOutput::ok(format!("{}\n", tag))))
+}
+
+#[test]
+fn flags_tag_and_deploy_without_prompting() {
+    let runner = repo_at("v1.2.3");
+    let options = BumpOptions {
+        bump: Some(BumpType::Minor),
+        yes: true,
+        deploy: Some(DeployTarget::Version),
+        ..BumpOptions::default()
+    };
+
+    let report = VersionBumper::new(&context(&runner), options).run().unwrap();
+
+    assert_eq!(report.previous_version, "1.2.3");
+    assert_eq!(report.tag, "v1.3.0");
+    assert_eq!(
+        runner.command_lines()[1..],
+        [
+            "git tag -a v1.3.0 -m 'Version 1.3.0'",
+            "git push origin v1.3.0",
+            "mike deploy v1.3.0 --branch gh-pages --remote origin --push",
+        ]
+    );
+}
+
+#[test]
+fn set_version_accepts_a_tag_prefix_and_rejects_garbage() {
+    let runner = repo_at("v1.2.3");
+    let bumper = VersionBumper::new(&context(&runner), BumpOptions::default());
+
+    assert_eq!(bumper.parse_set_version("v2.0.0").unwrap(), "2.0.0");
+    assert!(matches!(bumper.parse_set_version("2.0"), Err(DocToolsError::Usage(_))));
+    assert!(matches!(bumper.parse_set_version("2.x.0.1"), Err(DocToolsError::Usage(_))));
+}
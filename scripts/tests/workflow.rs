@@ -0,0 +1,96 @@
+mod common;
+
+use std::sync::Arc;
+
+use doc_tools::exec::{CommandOutput, FakeRunner};
+use doc_tools::workflow::{StepStatus, Variables, Workflow};
+use doc_tools::{Config, Context, DocToolsError, Project};
+
+const RELEASE: &str = r#"
+name = "release"
+
+[vars]
+prerelease = false
+
+[[step]]
+name = "Tag"
+run = "bump-version"
+set = "1.0.0"
+
+[[step]]
+name = "Deploy"
+run = "deploy"
+if = "!prerelease"
+
+[[step]]
+name = "Announce"
+run = "command"
+command = ["echo", "released {new_tag} after {previous_version}"]
+"#;
+
+#[test]
+fn skips_conditional_steps_and_expands_variables() {
+    let root = common::scratch("workflow-prerelease");
+    let runner = Arc::new(FakeRunner::new().on("git describe --tags", CommandOutput::ok("v0.4.1\n")));
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let workflow = Workflow::parse(RELEASE).unwrap();
+    let (report, result) = workflow.run(&ctx, &[("prerelease".into(), "true".into())]);
+
+    result.unwrap();
+    let statuses: Vec<StepStatus> = report.steps.iter().map(|step| step.status).collect();
+    assert_eq!(statuses, [StepStatus::Ok, StepStatus::Skipped, StepStatus::Ok]);
+    assert_eq!(report.variables["new_tag"], "v1.0.0");
+
+    let lines = runner.command_lines();
+    assert!(lines.contains(&"git tag -a v1.0.0 -m 'Version 1.0.0'".to_string()));
+    assert!(lines.contains(&"echo 'released v1.0.0 after 0.4.1'".to_string()));
+    assert!(!lines.iter().any(|line| line.starts_with("mike")));
+}
+
+#[test]
+fn stops_at_the_first_failing_step() {
+    let root = common::scratch("workflow-failure");
+    let runner = Arc::new(
+        FakeRunner::new()
+            .on("git describe --tags", CommandOutput::ok("v0.4.1\n"))
+            .on("git push origin v1.0.0", CommandOutput::failed(128)),
+    );
+    let ctx = Context::new(Project::at(&root).unwrap(), Config::default(), runner.clone());
+
+    let (report, result) = Workflow::parse(RELEASE).unwrap().run(&ctx, &[]);
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind(), "step");
+    assert_eq!(error.to_string(), "step 'Tag' failed: git: Failed to push git tag v1.0.0 to remote.");
+    let statuses: Vec<StepStatus> = report.steps.iter().map(|step| step.status).collect();
+    assert_eq!(statuses, [StepStatus::Failed, StepStatus::NotRun, StepStatus::NotRun]);
+    assert!(matches!(&error, DocToolsError::Step { steps, .. } if *steps == report.steps));
+    assert!(!runner.command_lines().iter().any(|line| line.starts_with("echo")));
+}
+
+#[test]
+fn rejects_options_a_step_does_not_take() {
+    let error = Workflow::parse("[[step]]\nrun = \"deploy\"\nbump = \"major\"\n").unwrap_err();
+    assert_eq!(error.to_string(), "workflow: step 1 (deploy): 'bump' does not apply to a deploy step");
+
+    assert!(Workflow::parse("[[step]]\nrun = \"bump-version\"\n").is_err());
+    assert!(Workflow::parse("[[step]]\nrun = \"publish\"\n").is_err());
+    assert!(Workflow::parse("name = \"empty\"\n").is_err());
+}
+
+#[test]
+fn evaluates_conditions_and_placeholders() {
+    let mut vars = Variables::default();
+    vars.set("prerelease", "false".into());
+    vars.set("channel", "beta".into());
+
+    assert!(vars.evaluate("!prerelease").unwrap());
+    assert!(vars.evaluate("channel == \"beta\"").unwrap());
+    assert!(!vars.evaluate("channel != beta").unwrap());
+    assert!(vars.evaluate("missing").is_err());
+
+    assert_eq!(vars.expand("{channel} {{literal}}").unwrap(), "beta {literal}");
+    assert!(vars.expand("{unknown}").is_err());
+    assert!(vars.expand("{channel").is_err());
+}